@@ -0,0 +1,146 @@
+# English messages, built into the binary and used for any key another locale leaves out
+# Every line is `key = text`, { $name } is replaced by a value and \n starts a new line
+# Only this single-line subset of Fluent is read: multiline messages, .attributes, -terms and { $x -> ... }
+# selectors are rejected
+# Effect names, range names and rules text come from the config, translate them with effect-name-<id>,
+# range-name-<id> and rules-text-<effect id>[-<range id>] keys, run --locale-missing <locale> to list them
+
+## Card layout
+card-priority = Priority: { $priority }
+card-rarity = Rarity: { $rarity }
+card-cast = Cast: { $barnacles } barnacles
+card-withdraw = Withdraw: { $barnacles } barnacles
+card-effect = Effect: { $effects }
+card-effects = Effects: { $effects }
+card-range = Range: { $range }
+card-rules = Rules: { $rules }
+card-leftover = Leftover: { $leftover }
+
+## Rarities
+rarity-common = Common
+rarity-uncommon = Uncommon
+rarity-rare = Rare
+rarity-epic = Epic
+rarity-legendary = Legendary
+
+## Deck types
+deck-type-starter = Starter
+deck-type-journeyman = Journeyman
+deck-type-legendary = Legendary
+
+## Rounding
+rounding-down = down
+rounding-nearest = nearest
+rounding-up = up
+rounding-policy-floor = floor
+rounding-policy-half-even = half_even
+rounding-policy-ceil = ceil
+rounding-policy-exact = exact
+
+## Effects
+effect-magnitude = { $name } ({ $magnitude })
+effect-search = { $name } ({ $magnitude } { $rarity })
+effect-control-turn = { $name } ({ $duration } turn)
+effect-control-turns = { $name } ({ $duration } turns)
+effect-per-turn = { $name } ({ $magnitude }/turn for { $duration } turns)
+effect-every = { $name } ({ $magnitude } every { $tick } turns for { $duration } turns)
+
+## Range targets
+targets-count = { $count }
+targets-random = { $count } random
+targets-room = room
+targets-party = party
+targets-allies = allies
+targets-caster = caster
+targets-line = { $count } in a line
+targets-cone = cone spreading { $spread }
+
+## Rules text placeholders
+rules-one-target = one target
+rules-targets = { $count } targets
+rules-one-random-target = one random target
+rules-random-targets = { $count } random targets
+rules-room = everyone in the room
+rules-party = the whole party
+rules-allies = every ally
+rules-caster = the caster
+rules-line = { $count } targets in a line
+rules-cone = one target and up to { $spread } on either side of it
+rules-turn = 1 turn
+rules-turns = { $count } turns
+rules-every-turn = every turn
+rules-every = every { $count } turns
+
+## Conditions
+condition-priority-above = If the opponent casts a card with priority above { $priority } the same turn
+condition-target-shielded = If an enemy is shielded
+condition-on-withdraw = On withdraw
+
+## Leftover budget
+leftover-discarded = { $amount } budget discarded
+leftover-magnitude = { $amount } budget bought +{ $magnitude } { $effect }
+leftover-barnacles = { $amount } budget took { $barnacles } barnacles off the cast cost
+leftover-priority = { $amount } budget banked into priority
+
+## Interactive prompts, a space is added after each prompt
+prompt-could-not-parse = Could not parse { $input }!
+prompt-not-in-range = Not in range!
+prompt-budget = Card power budget: { $budget }
+prompt-priority-allocation = Enter priority allocation (even) ({ $min }..{ $max }):
+prompt-effect-count = Enter number of effects (1..{ $max }) (Cost per extra effect: { $surcharge }):
+prompt-effect = Enter effect type: (1..{ $max })..
+prompt-duration = Enter duration in turns (1..{ $max }):
+prompt-tick = Enter turns between ticks (1..{ $max }):
+prompt-range = Enter range type: (1..{ $max })..
+prompt-efficiency = Enter efficiency: (1..{ $max })..
+prompt-rarity = Enter rarity: (1..5)..
+prompt-card-name = Enter card name (? to generate one once the card is configured, <Enter> to exit):
+prompt-deck-card-name = Enter name for { $rarity } card { $number } (<Enter> to generate one):
+prompt-flavour = Enter flavour text (<Enter> for none):
+prompt-deck-type = 1: Starter\n2: Journeyman\n3: Legendary\nEnter deck type (1..3)...
+prompt-deck-name = Enter deck name:
+prompt-invalid-configuration = Invalid configuration!
+menu-effect = { $index }: { $name } (Cost: { $cost }{ $per_turn })
+menu-per-turn = /turn
+menu-unaffordable = N/A
+menu-range = { $index }: { $name } ({ $targets }) (Cost: { $cost })
+menu-efficiency = { $index }: { $name } (x{ $multiplier })
+card-generated = Generated Card:
+card-written = Wrote card to file: { $file }
+deck-generated = Generated deck: { $deck }
+deck-random-written = Wrote { $deck_type } { $theme } deck to { $file } (seed { $seed })
+
+## Reports
+priority-explain = { $rarity } card with { $allocation } priority allocated:
+rounding-report = { $deck } under { $policy } rounding (budget given to effects / exactly worth / charged / lost / mispriced):
+retier-already = { $card } is already { $rarity }
+retier-rarity = Rarity
+retier-power = Power
+retier-priority-allocation = Priority allocation
+retier-priority = Priority
+retier-cast = Cast
+retier-withdraw = Withdraw
+retier-effects = Effects
+
+## Help
+help-options = Options:
+help-deck-template = --deck-template Generate a deck template \nWarning: must be placed in folder of the same name as the deck and the file renamed to <deck_name>.card all in the decks/ folder)
+help-deck-from-template = --deck-from-template Generates a deck from a template (decks/<name>/<name>.deck, or .toml/.yaml/.yml/.ron)
+help-deck-generator = --deck-generator: Interactive deck generation
+help-deck-random = --deck-random [--archetype starter|journeyman|legendary] [--theme <theme>] [--seed N] [--name <deck>]: Writes a deck of cards picked with the config's theme weights and builds it (default: a Balanced starter deck with a random seed)
+help-deck-examples = --deck-examples: Generates example decks for all tiers
+help-generate-cards = --generate-cards: Generate induvidual cards (written to cards/ folder)
+help-config-check = --config-check: Validates { $path } and lists every error and warning
+help-deck-check = --deck-check <deck>: Validates a deck against the config, including what its card flow effects rely on
+help-card-explain = --card-explain <deck> [card] [--json]: Builds a deck's cards and shows every budget deduction and multiplier along the way
+help-rounding-report = --rounding-report <deck> [floor|half_even|ceil|exact]: Shows how much of each card's budget is lost to rounding (default: the config's rounding policy)
+help-priority-explain = --priority-explain <rarity> <priority allocation>: Shows how the priority model turns an allocation into a card's priority
+help-autofill = --autofill <deck> [min-effect <effect> <count>] [max-range <range> <count>] [priority <average>] [balanced] [--iterations N] [--seed N] [--write]: Picks the fields a deck's cards still have at their template placeholder values to meet the goals (--write saves it, keeping <file>.bak)
+help-retier = --retier <deck> <rarity|up|down> [card] [--write]: Proposes a deck's cards at another rarity with a rescaled priority allocation, old and new stats side by side (--write saves it, keeping <file>.bak)
+help-convert = --convert <input> <output>: Converts a deck or config between JSON (.deck/.json), TOML, YAML and RON by file extension
+help-migrate = --migrate [files...]: Upgrades decks and configs to the latest version in place, keeping <file>.bak backups (default: every deck and config)
+help-schema = --schema [folder]: Writes JSON Schemas for .deck and config files (default folder: schemas/)
+help-simulate = --simulate <deck> <opponent deck> [games] [seed]: Plays two decks against each other with every built-in strategy
+help-tune = --tune [win-rate <deck> <opponent deck> <target>] [parity <tolerance>] [--iterations N] [--games N] [--seed N]: Searches for a config meeting the targets (written to { $path })
+help-locale = --locale <locale>: Goes with any other option, prints cards and prompts with the messages in locales/<locale>.ftl, falling back to English for anything it leaves out
+help-locale-missing = --locale-missing <locale>: Lists the keys locales/<locale>.ftl has no translation for, with the English text to translate
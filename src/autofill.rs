@@ -0,0 +1,262 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{card::*, catalog::*, names::*};
+
+#[derive(Debug, Clone)]
+pub enum Goal {
+    // At least `count` cards carrying the effect
+    MinEffect { effect: String, count: usize },
+    // At most `count` cards using the range
+    MaxRange { range: String, count: usize },
+    AveragePriority { target: f32 },
+    // Damage, healing and support cards within one of each other
+    Balanced,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Damage,
+    Healing,
+    Support,
+}
+
+fn role(mechanic: Mechanic) -> Role {
+    match mechanic {
+        _ if mechanic.is_damage() => Role::Damage,
+        Mechanic::Heal | Mechanic::AcidHeal | Mechanic::Regeneration => Role::Healing,
+        _ => Role::Support,
+    }
+}
+
+impl Goal {
+    pub fn describe(&self) -> String {
+        match self {
+            Goal::MinEffect { effect, count } => format!("At least {} {} card(s)", count, effect),
+            Goal::MaxRange { range, count } => format!("At most {} {} card(s)", count, range),
+            Goal::AveragePriority { target } => format!("Average priority {}", target),
+            Goal::Balanced => String::from("Balanced damage, healing and support cards"),
+        }
+    }
+
+    pub fn measure(&self, cards: &[Card], config: &Config) -> f32 {
+        match self {
+            Goal::MinEffect { effect, .. } => cards.iter().filter(|card| card.effects.iter().any(|carried| same_id(&carried.id, effect))).count() as f32,
+            Goal::MaxRange { range, .. } => cards.iter().filter(|card| card.range.as_ref().is_some_and(|carried| same_id(&carried.id, range))).count() as f32,
+            Goal::AveragePriority { .. } => cards.iter().map(|card| card.priority as f32).sum::<f32>() / cards.len().max(1) as f32,
+            Goal::Balanced => {
+                let counts = [Role::Damage, Role::Healing, Role::Support].map(|wanted| {
+                    cards.iter().filter(|card| card.effects.first().and_then(|effect| config.mechanic(effect)).is_some_and(|mechanic| role(mechanic) == wanted)).count()
+                });
+                (counts.iter().max().unwrap_or(&0) - counts.iter().min().unwrap_or(&0)) as f32
+            }
+        }
+    }
+
+    pub fn loss(&self, measured: f32) -> f32 {
+        match self {
+            Goal::MinEffect { count, .. } => (*count as f32 - measured).max(0.0),
+            Goal::MaxRange { count, .. } => (measured - *count as f32).max(0.0),
+            Goal::AveragePriority { target } => (measured - target).abs(),
+            Goal::Balanced => (measured - 1.0).max(0.0),
+        }
+    }
+}
+
+// Every card that fails to build costs this much, so the search always prefers a deck that builds
+const FAILED_BUILD_LOSS: f32 = 10.0;
+
+pub struct AutofillOptions {
+    pub iterations: u32,
+    pub seed: u64,
+}
+
+impl Default for AutofillOptions {
+    fn default() -> Self {
+        AutofillOptions { iterations: 500, seed: 0 }
+    }
+}
+
+// Which fields of a card still hold the `CardInput::new` placeholder and may be picked
+#[derive(Debug, Clone, Copy)]
+pub struct FreeFields {
+    pub name: bool,
+    pub efficiency: bool,
+    pub priority_allocation: bool,
+    pub range: bool,
+    pub effects: bool,
+}
+
+impl FreeFields {
+    pub fn of(input: &CardInput) -> Self {
+        let placeholder = CardInput::new(input.rarity.clone());
+        let same_effects = input.effects.len() == placeholder.effects.len()
+            && input.effects.iter().zip(placeholder.effects.iter()).all(|(effect, other)| same_id(&effect.id, &other.id) && effect.magnitude == other.magnitude);
+        FreeFields {
+            name: input.name == placeholder.name,
+            efficiency: same_id(&input.efficiency.id, &placeholder.efficiency.id),
+            priority_allocation: input.priority_allocation == placeholder.priority_allocation,
+            range: same_id(&input.range.id, &placeholder.range.id),
+            effects: same_effects,
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.name || self.efficiency || self.priority_allocation || self.range || self.effects
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        [(self.name, "name"), (self.efficiency, "efficiency"), (self.priority_allocation, "priority allocation"), (self.range, "range"), (self.effects, "effects")]
+            .into_iter()
+            .filter_map(|(free, name)| free.then_some(name))
+            .collect()
+    }
+}
+
+pub struct AutofillResult {
+    pub deck: DeckInputs,
+    pub before: Vec<f32>,
+    pub after: Vec<f32>,
+    pub failed: usize,
+    pub accepted: u32,
+}
+
+// Picks a new value for one free field, `field` is an index from `free_field_indices`:
+// 0 efficiency, 1 priority allocation, 2 range, 3 effects (names are picked after the search)
+fn pick_field(input: &mut CardInput, field: usize, config: &Config, rng: &mut StdRng) {
+    match field {
+        0 => {
+            if let Some(tier) = config.efficiency.choose(rng) {
+                input.efficiency = tier.efficiency();
+            }
+        }
+        1 => input.priority_allocation = rng.gen_range(1..config.rarity_ranges.get(&input.rarity).min.max(2)),
+        2 => {
+            if let Some(definition) = config.range_catalog.choose(rng) {
+                input.range = definition.range();
+            }
+        }
+        _ => {
+            if let Some(definition) = config.effect_catalog.choose(rng) {
+                input.effects = vec![Effect::new(&definition.id)];
+            }
+        }
+    }
+}
+
+fn free_field_indices(free: &FreeFields) -> Vec<usize> {
+    [free.efficiency, free.priority_allocation, free.range, free.effects].iter().enumerate().filter_map(|(index, free)| free.then_some(index)).collect()
+}
+
+fn mutate(deck: &DeckInputs, free: &[FreeFields], config: &Config, rng: &mut StdRng) -> DeckInputs {
+    let mut candidate = deck.clone();
+    let cards: Vec<usize> = (0..free.len()).filter(|index| !free_field_indices(&free[*index]).is_empty()).collect();
+    if let Some(card) = cards.choose(rng) {
+        if let Some(field) = free_field_indices(&free[*card]).choose(rng) {
+            pick_field(&mut candidate.inputs[*card], *field, config, rng);
+        }
+    }
+    candidate
+}
+
+// Every card is built with the same seed so only the deck changes between candidates
+fn score(deck: &DeckInputs, goals: &[Goal], config: &Config, seed: u64) -> (f32, Vec<f32>, usize) {
+    let results: Vec<Result<Card, String>> = deck.inputs.iter().map(|input| input.build(config, &mut StdRng::seed_from_u64(seed))).collect();
+    let cards: Vec<Card> = results.iter().filter_map(|result| result.as_ref().ok().cloned()).collect();
+    let failed = results.len() - cards.len();
+    let measured: Vec<f32> = goals.iter().map(|goal| goal.measure(&cards, config)).collect();
+    let loss = goals.iter().zip(measured.iter()).map(|(goal, value)| goal.loss(*value)).sum::<f32>() + failed as f32 * FAILED_BUILD_LOSS;
+    (loss, measured, failed)
+}
+
+// Seeded search over the free fields: each one starts from a random pick, then single field changes are kept
+// unless they make the deck score worse
+pub fn autofill(deck: &DeckInputs, goals: &[Goal], config: &Config, options: &AutofillOptions) -> AutofillResult {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let free: Vec<FreeFields> = deck.inputs.iter().map(FreeFields::of).collect();
+    let (_, before, _) = score(deck, goals, config, options.seed);
+    let mut best = deck.clone();
+    for (input, free) in best.inputs.iter_mut().zip(free.iter()) {
+        for field in free_field_indices(free) {
+            pick_field(input, field, config, &mut rng);
+        }
+    }
+    let (mut best_loss, mut best_measured, mut best_failed) = score(&best, goals, config, options.seed);
+    let mut accepted = 0;
+    for _ in 0..options.iterations {
+        let candidate = mutate(&best, &free, config, &mut rng);
+        let (loss, measured, failed) = score(&candidate, goals, config, options.seed);
+        if loss <= best_loss {
+            best = candidate;
+            best_loss = loss;
+            best_measured = measured;
+            best_failed = failed;
+            accepted += 1;
+        }
+    }
+    // Names do not affect the score, so cards are only named once everything else is picked
+    let mut names = NameGenerator::new(config);
+    for (input, _) in best.inputs.iter().zip(free.iter()).filter(|(_, free)| !free.name) {
+        names.take(&input.name);
+    }
+    for (input, _) in best.inputs.iter_mut().zip(free.iter()).filter(|(_, free)| free.name) {
+        input.name = names.name(&input.rarity, Some(&input.range), &input.effects, &mut rng);
+    }
+    AutofillResult { deck: best, before, after: best_measured, failed: best_failed, accepted }
+}
+
+pub fn autofill_report(original: &DeckInputs, goals: &[Goal], options: &AutofillOptions, result: &AutofillResult, config: &Config) -> String {
+    let mut report = format!("Autofill ({} iterations, seed {}), {} changes kept\n\nGoals:\n", options.iterations, options.seed, result.accepted);
+    for ((goal, before), after) in goals.iter().zip(result.before.iter()).zip(result.after.iter()) {
+        let status = if goal.loss(*after) == 0.0 { "met" } else { "not met" };
+        report.push_str(&format!("\t{}: {:.2} -> {:.2} ({})\n", goal.describe(), before, after, status));
+    }
+    if result.failed > 0 {
+        report.push_str(&format!("\t{} card(s) still fail to build\n", result.failed));
+    }
+    report.push_str("\nCards:\n");
+    for (original, filled) in original.inputs.iter().zip(result.deck.inputs.iter()) {
+        let free = FreeFields::of(original);
+        if !free.any() {
+            report.push_str(&format!("\t{}: fully specified, kept as is\n", original.name));
+            continue;
+        }
+        let effects: Vec<String> = filled.effects.iter().map(|effect| config.definition(effect).map_or(effect.id.clone(), |definition| definition.name.clone())).collect();
+        report.push_str(&format!(
+            "\t{} (picked {}): {} efficiency, priority allocation {}, {}, {}\n",
+            filled.name,
+            free.names().join(", "),
+            filled.efficiency.id,
+            filled.priority_allocation,
+            config.describe_range(&filled.range),
+            effects.join(" + ")
+        ));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autofill_meets_its_goals_and_keeps_set_fields() {
+        let config = Config::default();
+        let mut deck = DeckInputs::new(DeckType::Journeyman);
+        deck.inputs[0].name = String::from("Kept");
+        deck.inputs[0].effects = vec![Effect::new("Shield")];
+        let goals = [Goal::MinEffect { effect: String::from("heal"), count: 2 }, Goal::MaxRange { range: String::from("Single"), count: 1 }];
+        let result = autofill(&deck, &goals, &config, &AutofillOptions { iterations: 2000, seed: 2 });
+        assert_eq!(result.failed, 0);
+        assert!(goals.iter().zip(result.after.iter()).all(|(goal, measured)| goal.loss(*measured) == 0.0), "{:?}", result.after);
+        assert_eq!((result.deck.inputs[0].name.as_str(), result.deck.inputs[0].effects[0].id.as_str()), ("Kept", "Shield"));
+        let names: Vec<&String> = result.deck.inputs.iter().map(|input| &input.name).collect();
+        assert!(names.iter().enumerate().all(|(index, name)| !names[..index].contains(name)));
+    }
+
+    #[test]
+    fn only_placeholder_fields_are_free() {
+        let mut input = CardInput::new(Rarity::Rare);
+        input.range = Range::new("AoE");
+        assert_eq!(FreeFields::of(&input).names(), ["name", "efficiency", "priority allocation", "effects"]);
+    }
+}
@@ -1,5 +1,6 @@
-use std::{fs::OpenOptions, io::{Read, Write}};
+use std::{collections::BTreeMap, fs::OpenOptions, io::{Read, Seek, Write}, path::Path};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Default)]
@@ -10,14 +11,17 @@ pub enum DeckType {
     Legendary
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct DeckInputs {
+    #[serde(default)]
+    pub version: u32,
     pub inputs: [CardInput; 5]
 }
 
 impl DeckInputs {
     pub fn new(deck_type: DeckType) -> Self {
         DeckInputs {
+            version: <DeckInputs as Versioned>::VERSION,
             inputs: match deck_type {
                 DeckType::Starter => {
                     [
@@ -51,14 +55,29 @@ impl DeckInputs {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+impl DeckInputs {
+    pub fn build<R: Rng + ?Sized>(&self, config: &Config, rng: &mut R) -> Result<Vec<Card>, String> {
+        self.inputs
+            .iter()
+            .map(|input| input.build(config, rng).map_err(|err| format!("{}: {}", input.name, err)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct CardInput {
     pub name: String,
     pub rarity: Rarity,
     pub efficiency: Efficiency,
+    #[schemars(range(min = 1))]
     pub priority_allocation: i32,
     pub range: Range,
-    pub effect: Effect,
+    #[schemars(length(min = 1))]
+    pub effects: Vec<Effect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditionals: Vec<ConditionalEffect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flavour: Option<String>,
 }
 
 impl CardInput {
@@ -66,24 +85,50 @@ impl CardInput {
         CardInput { 
             name: format!("{:?}", rarity), 
             rarity, 
-            efficiency: Efficiency::Bad, 
+            efficiency: Efficiency::new("Bad"),
             priority_allocation: 1, 
-            range: Range::Single, 
-            effect: Effect::Damage(0) 
+            range: Range::new("Single"),
+            effects: vec![Effect::new("Damage")],
+            conditionals: Vec::new(),
+            flavour: None,
         }
     }
 
+    pub fn build<R: Rng + ?Sized>(&self, config: &Config, rng: &mut R) -> Result<Card, String> {
+        Card::new_with_rng(self.name.clone(), self.rarity.clone(), self.efficiency.clone(), config.clone(), rng)
+            .with_priority_allocation(self.priority_allocation)
+            .with_range(self.range.clone())
+            .with_conditional_effects(self.effects.clone(), self.conditionals.clone())
+            .with_flavour(self.flavour.clone())
+            .build()
+    }
+
+    // Builds the card with a ledger, which is kept even when the card fails to build
+    pub fn explain<R: Rng + ?Sized>(&self, config: &Config, rng: &mut R) -> (Ledger, Result<Card, String>) {
+        let mut card = Card::new_with_rng(self.name.clone(), self.rarity.clone(), self.efficiency.clone(), config.clone(), rng);
+        let result = card
+            .with_ledger()
+            .with_priority_allocation(self.priority_allocation)
+            .with_range(self.range.clone())
+            .with_conditional_effects(self.effects.clone(), self.conditionals.clone())
+            .with_flavour(self.flavour.clone())
+            .build();
+        (card.ledger.unwrap_or_default(), result)
+    }
+
     pub fn apply_configuration(&mut self, card: &Card) {
         assert_eq!(self.rarity, card.rarity, "Error in configuration, rarity does not match!");
         self.name = card.name.clone();
         self.efficiency = card.efficiency.clone();
         self.priority_allocation = card.priority_allocation;
         self.range = card.range.as_ref().unwrap().clone();
-        self.effect = card.effect.as_ref().unwrap().clone();
+        self.effects = card.effects.clone();
+        self.conditionals = card.conditionals.clone();
+        self.flavour = card.flavour.clone();
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct RarityRanges {
    pub common: PowerRange,
     pub uncommon: PowerRange,
@@ -93,20 +138,26 @@ pub struct RarityRanges {
 }
 
 impl RarityRanges {
-    pub fn get_power(&self, rng: &mut ThreadRng, rarity: &Rarity) -> i32 {
+    pub fn get(&self, rarity: &Rarity) -> &PowerRange {
         match rarity {
-            Rarity::Common => self.common.get(rng),
-            Rarity::Uncommon => self.uncommon.get(rng),
-            Rarity::Rare => self.rare.get(rng),
-            Rarity::Epic => self.epic.get(rng),
-            Rarity::Legendary => self.legendary.get(rng),
+            Rarity::Common => &self.common,
+            Rarity::Uncommon => &self.uncommon,
+            Rarity::Rare => &self.rare,
+            Rarity::Epic => &self.epic,
+            Rarity::Legendary => &self.legendary,
         }
     }
+
+    pub fn get_power<R: Rng + ?Sized>(&self, rng: &mut R, rarity: &Rarity) -> i32 {
+        self.get(rarity).get(rng)
+    }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct PowerRange {
+    #[schemars(range(min = 1))]
     pub min: i32,
+    #[schemars(range(min = 1))]
     pub max: i32,
 }
 
@@ -115,7 +166,7 @@ impl PowerRange {
         PowerRange { min, max }
     }
 
-    pub fn get(&self, rng: &mut ThreadRng) -> i32 {
+    pub fn get<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
         if rng.gen_bool(0.5) {
             self.min
         } else {
@@ -135,12 +186,17 @@ impl Default for RarityRanges {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct RarityPriorityModifiers {
+    #[schemars(schema_with = "crate::schema::positive_number")]
     pub common: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
     pub uncommon: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
     pub rare: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
     pub epic: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
     pub legendary: f32
 }
 
@@ -157,7 +213,7 @@ impl Default for RarityPriorityModifiers {
 }
 
 impl RarityPriorityModifiers {
-    fn get_modifier(&self, rarity: &Rarity) -> f32 {
+    pub fn get_modifier(&self, rarity: &Rarity) -> f32 {
         match rarity {
             Rarity::Common => self.common,
             Rarity::Uncommon => self.uncommon,
@@ -168,190 +224,385 @@ impl RarityPriorityModifiers {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
 pub struct Config {
+    #[serde(default)]
+    pub version: u32,
     pub rarity_ranges: RarityRanges,
     pub power_to_priority: RarityPriorityModifiers,
-    pub damage_range_modifiers: RangeModifiers,
-    pub heal_range_modifiers: RangeModifiers,
-    pub acid_heal_range_modifiers: RangeModifiers,
-    pub shield_heal_range_modifiers: RangeModifiers,
+    #[serde(default)]
+    pub priority: PriorityModel,
+    #[serde(default = "builtin_catalog")]
+    pub effect_catalog: Vec<EffectDefinition>,
+    #[serde(default = "builtin_ranges")]
+    pub range_catalog: Vec<RangeDefinition>,
+    // Cheapest a card carrying any control effect may be
+    #[serde(default = "default_min_control_barnacles")]
+    #[schemars(range(min = 0))]
+    pub min_control_barnacles: i32,
+    #[serde(default)]
+    pub condition_costs: ConditionCosts,
+    #[serde(default = "default_efficiency")]
+    pub efficiency: Vec<EfficiencyTier>,
+    #[serde(default)]
+    pub barnacle_pricing: BarnaclePricing,
+    #[serde(default = "default_extra_effect_surcharge")]
+    #[schemars(range(min = 0))]
+    pub extra_effect_surcharge: i32,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+    #[serde(default)]
+    pub leftover: LeftoverPolicy,
+    #[serde(default = "builtin_themes")]
+    pub themes: Vec<Theme>,
+    #[serde(default)]
+    pub names: NameLists,
+    #[serde(default = "builtin_templates")]
+    pub rules_templates: Vec<RulesTemplate>,
+    #[serde(default)]
+    pub simulation: MatchRules,
+}
+
+fn default_extra_effect_surcharge() -> i32 {
+    2
+}
+
+fn default_min_control_barnacles() -> i32 {
+    3
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
+            version: <Config as Versioned>::VERSION,
             rarity_ranges: RarityRanges::default(),
             power_to_priority: RarityPriorityModifiers::default(),
-            damage_range_modifiers: RangeModifiers::new(Effect::Damage(0)),
-            heal_range_modifiers: RangeModifiers::new(Effect::Heal(0)),
-            acid_heal_range_modifiers: RangeModifiers::new(Effect::AcidHeal(0)),
-            shield_heal_range_modifiers: RangeModifiers::new(Effect::Shield(0)),
+            priority: PriorityModel::default(),
+            effect_catalog: builtin_catalog(),
+            range_catalog: builtin_ranges(),
+            min_control_barnacles: default_min_control_barnacles(),
+            condition_costs: ConditionCosts::default(),
+            efficiency: default_efficiency(),
+            barnacle_pricing: BarnaclePricing::default(),
+            extra_effect_surcharge: default_extra_effect_surcharge(),
+            rounding: RoundingPolicy::default(),
+            leftover: LeftoverPolicy::default(),
+            themes: builtin_themes(),
+            names: NameLists::default(),
+            rules_templates: builtin_templates(),
+            simulation: MatchRules::default(),
         }
     }
 }
 
-impl Config {
-    fn get_effect_range_modifier(&self, effect: &Effect, range: &Range) -> f32 {
-        match *effect {
-            Effect::Heal(_) => self.heal_range_modifiers.get_modifier(range),
-            Effect::AcidHeal(_) => self.acid_heal_range_modifiers.get_modifier(range),
-            Effect::Damage(_) => self.damage_range_modifiers.get_modifier(range),
-            Effect::Shield(_) => self.shield_heal_range_modifiers.get_modifier(range),
-        }
+pub fn load_config() -> Config {
+    let config = read_config();
+    let report = config.validate();
+    report.print();
+    if !report.is_valid() {
+        eprintln!("Invalid config in {}, run --config-check for details", crate::PATH);
+        std::process::exit(1);
     }
+    config
 }
 
-pub fn load_config() -> Config {
-    let mut config_file = OpenOptions::new().read(true).write(true).create(true).open(crate::PATH).expect("Could not load file!");
+pub fn read_config() -> Config {
+    if !Path::new(crate::PATH).exists() {
+        if let Some(path) = crate::ALTERNATE_CONFIG_PATHS.iter().map(Path::new).find(|path| path.exists()) {
+            return read_document(path).unwrap_or_else(|err| {
+                eprintln!("Could not parse config: {}", err);
+                std::process::exit(1);
+            });
+        }
+    }
+    let mut config_file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(crate::PATH).expect("Could not load file!");
     let mut contents = String::new();
     config_file.read_to_string(&mut contents).expect("Could not read file!");
-    let (config, config_empty) = if contents.trim().is_empty() {
-        (Config::default(), true)
-    } else {
-        match serde_json::from_str(contents.as_str()) {
-            Ok(deserialize) => (deserialize, false),
-            Err(_) => {
-                let _ = config_file.set_len(0); 
-                let _ = config_file.flush();
-                (Config::default(), true) 
-            }
-        }
-    };
-    if config_empty {
-        let _ = config_file.write_all(serde_json::to_string_pretty(&config).unwrap().as_bytes());
+    if !contents.trim().is_empty() {
+        // A config that fails to parse is the user's to fix, it is never replaced
+        return parse_document(Format::Json, contents.as_str()).unwrap_or_else(|err| {
+            eprintln!("Could not parse {}: {}", crate::PATH, err);
+            std::process::exit(1);
+        });
     }
+    let config = Config::default();
+    let _ = config_file.set_len(0);
+    let _ = config_file.rewind();
+    let _ = config_file.write_all(serde_json::to_string_pretty(&config).unwrap().as_bytes());
     config
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
-pub struct RangeModifiers {
-    pub single: f32,
-    pub multiple: f32,
-    pub aoe: f32,
-    pub aoe_extended: f32,
-}
+// Budget per point of magnitude for each range id in the range catalog
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema, Default)]
+#[serde(transparent)]
+pub struct RangeModifiers(pub BTreeMap<String, f32>);
 
 impl RangeModifiers {
-    pub fn get_modifier(&self, range: &Range) -> f32 {
-        match *range {
-            Range::Single => self.single,
-            Range::Multiple => self.multiple,
-            Range::AoE => self.aoe,
-            Range::ExtendedAoE => self.aoe_extended,
-        }
+    pub fn get(&self, range: &str) -> Option<f32> {
+        self.0.iter().find(|(id, _)| same_id(id, range)).map(|(_, modifier)| *modifier)
     }
 
-    pub fn new(effect: Effect) -> Self {
-        match effect {
-            Effect::Damage(_) => RangeModifiers { single: 1.0, multiple: 1.25, aoe: 0.875, aoe_extended: 0.75 },
-            Effect::Heal(_) => RangeModifiers { single: 1.5, multiple: 2.0, aoe: 1.25, aoe_extended: 1.5 },
-            Effect::AcidHeal(_) => RangeModifiers { single: 1.25, multiple: 1.25, aoe: 1.75, aoe_extended: 2.0 },
-            Effect::Shield(_) => RangeModifiers { single: 1.5, multiple: 2.0, aoe: 1.25, aoe_extended: 1.5 },
-        }
+    // Ranges the table does not list scale its cheapest modifier by the range's own modifier
+    pub fn get_modifier(&self, range: &RangeDefinition) -> f32 {
+        self.get(&range.id).unwrap_or_else(|| self.0.values().copied().reduce(f32::min).unwrap_or(1.0) * range.modifier)
     }
 }
 
-impl Default for RangeModifiers {
-    fn default() -> Self {
-        RangeModifiers { single: 1.0, multiple: 1.0, aoe: 1.0, aoe_extended: 1.0 }
-    }
-}
+use rand::Rng;
 
-use rand::{rngs::ThreadRng, Rng};
+use crate::{catalog::*, conditions::*, formats::*, leftover::*, ledger::*, locale::*, migrations::Versioned, names::*, priority::*, rounding::*, simulator::MatchRules, templates::*, themes::*};
 
-pub const DEFAULT_PRIORITY: i32 = 11;
 pub const PADDING: usize = 36;
+pub const MAX_EFFECTS: i32 = 3;
+pub const MAX_DURATION: i32 = 10;
 
 pub fn pad_right(string: String, len: usize, whitespace_ch: char) -> String {
     let mut padded = String::with_capacity(len); 
     padded.push_str(string.as_str());
-    for _ in 0..(len - string.len()) {
+    for _ in 0..len.saturating_sub(string.chars().count()) {
     padded.push(whitespace_ch);
     }
 
     padded
 }
 
-pub fn apply_multiplier(value: i32, multiplier: f32) -> i32 {
-    (value as f32 * multiplier).floor() as i32
+pub fn apply_multiplier(value: i32, multiplier: f32, rounding: RoundingPolicy) -> i32 {
+    rounding.round(value as f32 * multiplier)
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Rarity {
+    #[serde(alias = "common")]
     Common,
+    #[serde(alias = "uncommon")]
     Uncommon,
+    #[serde(alias = "rare")]
     Rare,
+    #[serde(alias = "epic")]
     Epic,
+    #[serde(alias = "legendary")]
     Legendary,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum Efficiency {
-    Bad,
-    Normal,
-    Good,
+// Names a tier of the config's efficiency table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Efficiency {
+    #[serde(rename = "type")]
+    pub id: String,
+}
+
+impl Efficiency {
+    pub fn new(id: &str) -> Self {
+        Efficiency { id: String::from(id) }
+    }
+}
+
+// A card's barnacle price is divided by its efficiency multiplier
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct EfficiencyTier {
+    pub id: String,
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub multiplier: f32,
+}
+
+impl EfficiencyTier {
+    pub fn matches(&self, id: &str) -> bool {
+        same_id(&self.id, id)
+    }
+
+    pub fn efficiency(&self) -> Efficiency {
+        Efficiency::new(&self.id)
+    }
+}
+
+pub fn default_efficiency() -> Vec<EfficiencyTier> {
+    [("Bad", 0.75), ("Normal", 1.0), ("Good", 1.5)]
+        .into_iter()
+        .map(|(id, multiplier)| EfficiencyTier { id: String::from(id), multiplier })
+        .collect()
+}
+
+fn multiplier_from_efficiency(efficiency: &Efficiency, config: &Config) -> f32 {
+    config.efficiency_tier(efficiency).map_or(1.0, |tier| tier.multiplier)
+}
+
+// Barnacle prices left once effects are priced by their catalog entry
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct BarnaclePricing {
+    // Scales every effect's barnacle_multiplier
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub effect_multiplier: f32,
+    // Barnacles per point of range cost
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub range_multiplier: f32,
+    // Share of the cast price paid to withdraw a card
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub withdraw_fraction: f32,
+    #[schemars(range(min = 0))]
+    pub min_withdraw: i32,
 }
 
-fn multiplier_from_efficiency(efficiency: &Efficiency) -> f32 {
-    match efficiency {
-        Efficiency::Bad => 0.75,
-        Efficiency::Normal => 1.0,
-        Efficiency::Good => 1.5,
+impl Default for BarnaclePricing {
+    fn default() -> Self {
+        BarnaclePricing { effect_multiplier: 1.0, range_multiplier: 1.0, withdraw_fraction: 1.0 / 3.0, min_withdraw: 1 }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", content = "magnitude")]
-pub enum Effect {
-    Heal(i32),
-    AcidHeal(i32),
-    Damage(i32),
-    Shield(i32),
+// An entry of the config's effect catalog, `type` is the catalog id
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Effect {
+    #[serde(rename = "type")]
+    pub id: String,
+    #[serde(default)]
+    #[schemars(range(min = 0))]
+    pub magnitude: i32,
+    // Over time and control effects only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub duration: Option<i32>,
+    // Over time effects only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub tick: Option<i32>,
+    // Search effects only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<Rarity>,
+}
+
+pub fn default_search_rarity() -> Rarity {
+    Rarity::Common
+}
+
+pub fn default_control_duration() -> i32 {
+    1
+}
+
+pub fn default_duration() -> i32 {
+    3
+}
+
+pub fn default_tick() -> i32 {
+    1
 }
 
 impl Effect {
-    pub fn to_string(&self) -> String {
-        match self {
-            Effect::Heal(magnitude) => format!("Heal ({})", magnitude),
-            Effect::AcidHeal(magnitude) => format!("Acid Heal ({})", magnitude),
-            Effect::Damage(magnitude) => format!("Damage ({})", magnitude),
-            Effect::Shield(magnitude) => format!("Shield ({})", magnitude),
+    pub fn new(id: &str) -> Effect {
+        Effect { id: String::from(id), magnitude: 0, duration: None, tick: None, rarity: None }
+    }
+
+    pub fn magnitude(&self) -> i32 {
+        self.magnitude
+    }
+
+    pub fn with_magnitude(&self, magnitude: i32) -> Effect {
+        Effect { magnitude, ..self.clone() }
+    }
+
+    pub fn with_timing(&self, duration: i32, tick: i32) -> Effect {
+        Effect { duration: Some(duration), tick: Some(tick), ..self.clone() }
+    }
+
+    pub fn with_duration(&self, duration: i32) -> Effect {
+        Effect { duration: Some(duration), ..self.clone() }
+    }
+
+    pub fn with_rarity(&self, rarity: Rarity) -> Effect {
+        Effect { rarity: Some(rarity), ..self.clone() }
+    }
+
+    // (duration, tick) for effects that last multiple turns
+    pub fn timing(&self) -> Option<(i32, i32)> {
+        self.duration.zip(self.tick)
+    }
+
+    // Number of times the magnitude is applied
+    pub fn ticks(&self) -> i32 {
+        match self.timing() {
+            Some((duration, tick)) => (duration.max(1) + tick.max(1) - 1) / tick.max(1),
+            None => 1,
         }
     }
+
+    pub fn total_magnitude(&self) -> i32 {
+        self.magnitude * self.ticks()
+    }
 }
 
 pub fn cost_from_effect(effect: Effect, budget: i32, range: &Option<Range>, config: &Config) -> (Option<Effect>, i32) {
-    let effect_modifier = config.get_effect_range_modifier(&effect, &range.as_ref().expect("No Range in card... How?"));
-    let budget = apply_multiplier(budget, 1.0 / effect_modifier);
-    match effect {
-        Effect::Heal(_) => (Some(Effect::Heal(budget)), apply_multiplier(budget, effect_modifier)),
-        Effect::AcidHeal(_) => (Some(Effect::AcidHeal(budget)), apply_multiplier(budget, effect_modifier)),
-        Effect::Damage(_) => (Some(Effect::Damage(budget)), apply_multiplier(budget, effect_modifier)),
-        Effect::Shield(_) => (Some(Effect::Shield(budget)), apply_multiplier(budget, effect_modifier)),
+    let (effect, used) = price_effect(effect, Fraction::from(budget), range, config);
+    (effect, used.floor())
+}
+
+// Prices an effect from a budget that may be fractional under exact rounding, returning what it costs
+pub fn price_effect(effect: Effect, budget: Fraction, range: &Option<Range>, config: &Config) -> (Option<Effect>, Fraction) {
+    let Some(definition) = config.definition(&effect) else {
+        return (None, Fraction::zero());
+    };
+    let effect = definition.normalise(effect);
+    let Some(effect_modifier) = config.effect_modifier(&effect, range.as_ref().expect("No Range in card... How?")) else {
+        return (None, Fraction::zero());
+    };
+    // Control effects cost a fixed amount per turn, they are unavailable when the budget cannot cover it
+    if definition.mechanic.kind() == EffectKind::Control {
+        let cost = config.rounding.scale(Fraction::from(effect.duration.unwrap_or(default_control_duration())), effect_modifier);
+        return if cost <= budget { (Some(effect), cost) } else { (None, Fraction::zero()) };
+    }
+    let mut magnitude = config.rounding.divide(budget, effect_modifier).floor();
+    // Rounding up can buy a magnitude that costs more than the budget once it is priced back, step down until it fits
+    while magnitude > 0 && config.rounding.scale(Fraction::from(magnitude), effect_modifier) > budget {
+        magnitude -= 1;
     }
+    (Some(effect.with_magnitude(magnitude)), config.rounding.scale(Fraction::from(magnitude), effect_modifier))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum Range {
-    Single,
-    Multiple,
-    AoE,
-    ExtendedAoE,
+// Exact budget an effect's magnitude, or duration for control effects, is worth
+pub fn exact_cost(effect: &Effect, range: &Option<Range>, config: &Config) -> Fraction {
+    let units = match config.mechanic(effect).map(|mechanic| mechanic.kind()) {
+        Some(EffectKind::Control) => effect.duration.unwrap_or(default_control_duration()),
+        _ => effect.magnitude,
+    };
+    range
+        .as_ref()
+        .and_then(|range| config.effect_modifier(effect, range))
+        .map_or(Fraction::zero(), |modifier| Fraction::from(units) * Fraction::approximate(modifier))
+}
+
+pub fn effect_surcharge(effect_count: usize, config: &Config) -> i32 {
+    config.extra_effect_surcharge * (effect_count as i32 - 1).max(0)
+}
+
+// Splits the budget evenly between effects, earlier effects take any remainder
+pub fn split_budget(budget: i32, effect_count: usize) -> Vec<i32> {
+    let (budget, count) = (budget.max(0), effect_count as i32);
+    (0..count).map(|index| budget / count + if index < budget % count { 1 } else { 0 }).collect()
 }
 
-pub fn cost_from_range(range: &Range) -> i32 {
-    match range {
-        Range::Single => 0,
-        Range::Multiple => 1,
-        Range::AoE => 2,
-        Range::ExtendedAoE => 4,
+// Exact rounding splits the fractional budget into equal shares instead
+fn split_shares(budget: Fraction, effect_count: usize, rounding: RoundingPolicy) -> Vec<Fraction> {
+    match rounding {
+        RoundingPolicy::Exact => vec![budget.max(Fraction::zero()) / Fraction::from(effect_count.max(1) as i32); effect_count],
+        _ => split_budget(budget.floor(), effect_count).into_iter().map(Fraction::from).collect(),
     }
 }
 
+// Names an entry in the config's range catalog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Range {
+    #[serde(rename = "type")]
+    pub id: String,
+}
+
+impl Range {
+    pub fn new(id: &str) -> Self {
+        Range { id: String::from(id) }
+    }
+}
+
+pub fn cost_from_range(range: &Range, config: &Config) -> i32 {
+    config.range_definition(range).map_or(0, |definition| definition.cost)
+}
+
 #[derive(Clone, Debug)]
 pub struct Card {
     pub name: String,
@@ -362,7 +613,17 @@ pub struct Card {
     pub rarity: Rarity,
     pub priority_allocation: i32,
     pub range: Option<Range>,
-    pub effect: Option<Effect>,
+    pub effects: Vec<Effect>,
+    pub conditionals: Vec<ConditionalEffect>,
+    pub flavour: Option<String>,
+    // Requested effects the budget could not pay for
+    pub unaffordable: Vec<Effect>,
+    pub ledger: Option<Ledger>,
+    // Fraction of a budget point left over under exact rounding
+    pub spare: Fraction,
+    pub rounding_loss: RoundingLoss,
+    // What became of the budget left after pricing, set by `build`
+    pub leftover: Option<Leftover>,
     pub config: Config
 }
 
@@ -373,98 +634,454 @@ where
     value >= min && value <= max
 }
 
-pub fn priority_from_budget(budget: i32, rarity: &Rarity, config: &Config) -> i32 {
-    if budget < 0 {
-        0
-    } else {
-        let value = (apply_multiplier(budget, config.power_to_priority.get_modifier(rarity))).clamp(1, DEFAULT_PRIORITY);
-        if value % 2 == 0 {
-            value + 1
-        } else {
-            value
-        }
-    }
-}
-
 impl Card {
     pub fn new(name: String, rarity: Rarity, efficiency: Efficiency, config: Config) -> Card {
-        let mut rng = rand::thread_rng();
+        Card::new_with_rng(name, rarity, efficiency, config, &mut rand::thread_rng())
+    }
+
+    pub fn new_with_rng<R: Rng + ?Sized>(name: String, rarity: Rarity, efficiency: Efficiency, config: Config, rng: &mut R) -> Card {
         Card {
             name, 
-            budget: config.rarity_ranges.get_power(&mut rng, &rarity),
+            budget: config.rarity_ranges.get_power(rng, &rarity),
             rarity,
-            priority: DEFAULT_PRIORITY,
+            priority: config.priority.max_priority,
             efficiency,
             priority_allocation: 0,
             barnacles: 100000000,
             range: None,
-            effect: None,
+            effects: Vec::new(),
+            conditionals: Vec::new(),
+            flavour: None,
+            unaffordable: Vec::new(),
+            ledger: None,
+            spare: Fraction::zero(),
+            rounding_loss: RoundingLoss::default(),
+            leftover: None,
             config
         }
     }
 
+    // Starts recording every budget deduction and multiplier, see `Ledger`
+    pub fn with_ledger(&mut self) -> &mut Card {
+        let power = self.config.rarity_ranges.get(&self.rarity);
+        let mut ledger = Ledger::default();
+        ledger.record("Power", format!("rolled {} from the {} range {}..{}", self.budget, self.rarity.label(), power.min, power.max), self.budget);
+        self.ledger = Some(ledger);
+        self
+    }
+
+    // Takes what an effect was charged from the budget, keeping any fraction left under exact rounding
+    fn spend(&mut self, share: Fraction, used: Fraction, exact: Fraction) {
+        let left = Fraction::from(self.budget) + self.spare - used;
+        self.budget = left.floor();
+        self.spare = left - Fraction::from(self.budget);
+        self.rounding_loss.available = self.rounding_loss.available + share;
+        self.rounding_loss.charged = self.rounding_loss.charged + used;
+        self.rounding_loss.exact = self.rounding_loss.exact + exact;
+    }
+
+    fn describe_pricing(&self, effect: &Effect, share: Fraction, used: Fraction, affordable: bool) -> String {
+        let Some(modifier) = self.range.as_ref().and_then(|range| self.config.effect_modifier(effect, range)) else {
+            return String::from("unknown effect or range");
+        };
+        if !affordable {
+            return format!("cannot be afforded from {} at modifier {}", share, modifier);
+        }
+        match self.config.mechanic(effect).map(|mechanic| mechanic.kind()) {
+            Some(EffectKind::Control) => format!("{} turns x {} per turn = {} of {}", effect.duration.unwrap_or(default_control_duration()), modifier, used, share),
+            _ => format!("{} / {} = magnitude {}, which costs {}", share, modifier, effect.magnitude, used),
+        }
+    }
+
+    fn record(&mut self, step: &str, detail: String) {
+        let budget = self.budget;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.record(step, detail, budget);
+        }
+    }
+
     pub fn with_priority_allocation(&mut self, priority_allocation: i32) -> &mut Card {
         self.priority_allocation = priority_allocation;
         self.budget -= priority_allocation;
+        self.record("Priority allocation", format!("-{}", priority_allocation));
         self
     }
 
     pub fn with_range(&mut self, range: Range) -> &mut Card {
-        let cost = cost_from_range(&range);
-        self.range = Some(range);
+        let cost = cost_from_range(&range, &self.config);
         self.budget -= cost;
+        self.record("Range", format!("{} costs {}", self.config.describe_range(&range), cost));
+        self.range = Some(range);
         self
     }
 
     pub fn with_effect(&mut self, effect: Effect) -> &mut Card {
-        let (created_effect, used) =
-            cost_from_effect(effect, self.budget, &self.range, &self.config);
-        self.budget -= used;
-        self.effect = created_effect;
+        self.with_effects(vec![effect])
+    }
+
+    pub fn with_effects(&mut self, effects: Vec<Effect>) -> &mut Card {
+        self.with_conditional_effects(effects, Vec::new())
+    }
+
+    // Conditional effects take a share of the budget like any other effect, after the card's own effects are priced
+    pub fn with_conditional_effects(&mut self, effects: Vec<Effect>, conditionals: Vec<ConditionalEffect>) -> &mut Card {
+        let count = effects.len() + conditionals.len();
+        let surcharge = effect_surcharge(count, &self.config);
+        self.budget -= surcharge;
+        if surcharge > 0 {
+            self.record("Extra effects", format!("-{} for {} effects", surcharge, count));
+        }
+        let mut shares = split_shares(Fraction::from(self.budget) + self.spare, count, self.config.rounding).into_iter();
+        for (effect, share) in effects.into_iter().zip(shares.by_ref()) {
+            let (created_effect, used) = price_effect(effect.clone(), share, &self.range, &self.config);
+            self.spend(share, used, created_effect.as_ref().map_or(Fraction::zero(), |effect| exact_cost(effect, &self.range, &self.config)));
+            let detail = self.describe_pricing(created_effect.as_ref().unwrap_or(&effect), share, used, created_effect.is_some());
+            match created_effect {
+                Some(created_effect) => {
+                    self.record(&self.config.describe_effect(&created_effect), detail);
+                    self.effects.push(created_effect);
+                }
+                None => {
+                    self.record(&effect.id, detail);
+                    self.unaffordable.push(effect);
+                }
+            }
+        }
+        for (conditional, share) in conditionals.into_iter().zip(shares) {
+            let (created_conditional, used) = cost_from_conditional(&conditional, share, &self.effects, &self.range, &self.config);
+            let multiplier = self.config.condition_costs.get_multiplier(&conditional.condition);
+            let exact = created_conditional
+                .as_ref()
+                .and_then(|conditional| conditional.payload(&self.effects))
+                .map_or(Fraction::zero(), |payload| exact_cost(&payload, &self.range, &self.config) * Fraction::approximate(multiplier));
+            self.spend(share, used, exact);
+            match created_conditional {
+                Some(created_conditional) => {
+                    let detail = format!("{} of the budget at condition multiplier {}, costs {}", share, multiplier, used);
+                    self.record(&created_conditional.describe(&self.effects, &self.config), detail);
+                    self.conditionals.push(created_conditional);
+                }
+                None => {
+                    self.record(&conditional.condition.to_string(), format!("cannot be afforded from {} at condition multiplier {}", share, multiplier));
+                    self.unaffordable.extend(conditional.payload(&self.effects));
+                }
+            }
+        }
+        self
+    }
+
+    pub fn with_flavour(&mut self, flavour: Option<String>) -> &mut Card {
+        self.flavour = flavour.filter(|flavour| !flavour.trim().is_empty());
         self
     }
 
     pub fn get_withdraw(&self) -> i32 {
-        apply_multiplier(self.barnacles, 1.0/3.0).max(1)
+        let pricing = &self.config.barnacle_pricing;
+        apply_multiplier(self.barnacles, pricing.withdraw_fraction, self.config.rounding).max(pricing.min_withdraw)
     }
 
     pub fn print_budget_mut(&mut self) -> &mut Card {
-        println!("Card power budget: {}", self.budget);
+        println!("{}", tr("prompt-budget", &[("budget", &self.budget)]));
         self
     }
 
-    pub fn to_string(&self) -> String {
-        // Rarity, Effect, Cost, Recast Cost
-        String::from(
-            format!("{}: \n\tPriority: {}\n\tRarity: {:?}\n\tCast: {} barnacles\n\tWithdraw: {} barnacles\n\tEffect: {}, Range: {:?}", self.name, self.priority, self.rarity, self.barnacles, self.get_withdraw(), self.effect.clone().unwrap().to_string(), self.range.clone().unwrap())
-        )
+    // Applies the leftover policy to whatever budget pricing did not spend
+    fn take_leftover(&mut self) {
+        let amount = Fraction::from(self.budget) + self.spare;
+        if amount <= Fraction::zero() || self.effects.is_empty() || !self.unaffordable.is_empty() {
+            return;
+        }
+        let leftover = match self.config.leftover {
+            LeftoverPolicy::Reject => return,
+            LeftoverPolicy::Discard => Leftover::Discarded { amount },
+            LeftoverPolicy::Magnitude => {
+                let grown = self.effects.iter().enumerate().find_map(|(index, effect)| Some((index, grow_effect(effect, amount, &self.range, &self.config)?)));
+                let Some((index, (grown, used))) = grown else {
+                    self.budget = 0;
+                    self.spare = Fraction::zero();
+                    self.leftover = Some(Leftover::Discarded { amount });
+                    return;
+                };
+                let exact = exact_cost(&grown, &self.range, &self.config) - exact_cost(&self.effects[index], &self.range, &self.config);
+                let magnitude = grown.magnitude - self.effects[index].magnitude;
+                self.spend(used, used, exact);
+                let leftover = Leftover::Magnitude { amount, effect: self.config.describe_effect(&grown.with_magnitude(0)), magnitude };
+                self.effects[index] = grown;
+                leftover
+            }
+            LeftoverPolicy::Barnacles => Leftover::Barnacles { amount, barnacles: self.config.rounding.scale(amount, self.config.barnacle_pricing.effect_multiplier).floor() },
+            LeftoverPolicy::Priority if amount.floor() > 0 => Leftover::Priority { amount: amount.floor() },
+            LeftoverPolicy::Priority => Leftover::Discarded { amount },
+        };
+        self.budget = 0;
+        self.spare = Fraction::zero();
+        self.leftover = Some(leftover);
     }
 
     pub fn build(&mut self) -> Result<Card, String> {
-        self.priority -= priority_from_budget(self.priority_allocation, &self.rarity, &self.config);
+        self.take_leftover();
+        let banked = match self.leftover {
+            Some(Leftover::Priority { amount }) => amount,
+            _ => 0,
+        };
+        let trace = self.config.priority_trace(self.priority_allocation + banked, &self.rarity);
+        self.priority = trace.priority;
         self.barnacles = get_barnacles(self);
-        if self.priority == DEFAULT_PRIORITY || self.barnacles == 0 {
-            Err(String::from(format!(
+        let has_control = self.effects.iter().any(|effect| self.config.mechanic(effect).is_some_and(|mechanic| mechanic.kind() == EffectKind::Control));
+        let lowest = if has_control { self.config.min_control_barnacles } else { 1 };
+        if let Some(Leftover::Barnacles { barnacles, .. }) = self.leftover.as_mut() {
+            let discounted = (self.barnacles - *barnacles).max(lowest.min(self.barnacles));
+            *barnacles = self.barnacles - discounted;
+            self.barnacles = discounted;
+        }
+        if self.ledger.is_some() {
+            if let Some(leftover) = self.leftover.clone() {
+                self.record("Leftover", leftover.to_string());
+            }
+            for step in trace.steps.iter() {
+                self.record("Priority", step.clone());
+            }
+            let (effects, conditionals, range) = barnacle_parts(self);
+            let multiplier = multiplier_from_efficiency(&self.efficiency, &self.config);
+            let discount = match self.leftover {
+                Some(Leftover::Barnacles { barnacles, .. }) => barnacles,
+                _ => 0,
+            };
+            let mut detail = format!("effects {} + conditionals {} + range {} = {}, / {} efficiency {:.2} = {}", effects, conditionals, range, effects + conditionals + range, self.efficiency.id, multiplier, self.barnacles + discount);
+            if discount > 0 {
+                detail.push_str(&format!(", - {} leftover = {}", discount, self.barnacles));
+            }
+            self.record("Barnacles", detail);
+            self.record("Withdraw", format!("{} x {:.2} = {}", self.barnacles, self.config.barnacle_pricing.withdraw_fraction, self.get_withdraw()));
+        }
+        if self.effects.is_empty() && self.unaffordable.is_empty() {
+            return Err(String::from("Card has no effects"));
+        }
+        if let Some(effect) = self.effects.iter().chain(self.unaffordable.iter()).find(|effect| self.config.definition(effect).is_none()) {
+            return Err(format!("Unknown effect type {}", effect.id));
+        }
+        if let Some(range) = self.range.as_ref().filter(|range| self.config.range_definition(range).is_none()) {
+            return Err(format!("Unknown range {}", range.id));
+        }
+        if let Some(range) = self.range.as_ref() {
+            let payloads: Vec<Effect> = self.conditionals.iter().filter_map(|conditional| conditional.payload(&self.effects)).collect();
+            if let Some(effect) = self.effects.iter().chain(payloads.iter()).find(|effect| self.config.aimed_at_own_side(effect, range)) {
+                return Err(format!("{} is aimed at enemies but {} only reaches the caster's side", effect.id, range.id));
+            }
+        }
+        if self.config.efficiency_tier(&self.efficiency).is_none() {
+            return Err(format!("Unknown efficiency {}", self.efficiency.id));
+        }
+        for (duration, tick) in self.effects.iter().filter_map(|effect| effect.timing()) {
+            if duration < 1 || tick < 1 || tick > duration {
+                return Err(format!("Effect duration {} must be at least 1 and tick {} between 1 and the duration", duration, tick));
+            }
+        }
+        if let Some(effect) = self.unaffordable.first() {
+            return Err(format!("Not enough budget for {}", self.config.describe_effect(effect)));
+        }
+        // Exact rounding can leave a fraction of a budget point on top of the whole points
+        let left = Fraction::from(self.budget) + self.spare;
+        if self.config.leftover == LeftoverPolicy::Reject && left > Fraction::zero() {
+            return Err(format!("{} budget left over, the leftover policy rejects cards that do not spend their whole budget", left));
+        }
+        let is_control = |effect: &Effect| self.config.mechanic(effect).is_some_and(|mechanic| mechanic.kind() == EffectKind::Control);
+        if let Some(duration) = self.effects.iter().filter(|effect| is_control(effect)).filter_map(|effect| effect.duration).find(|duration| *duration < 1) {
+            return Err(format!("Control effect duration {} must be at least 1", duration));
+        }
+        if self.effects.iter().any(is_control) && self.barnacles < self.config.min_control_barnacles {
+            return Err(format!(
+                "Control card costs {} barnacles, control cards must cost at least {}",
+                self.barnacles, self.config.min_control_barnacles
+            ));
+        }
+        for conditional in self.conditionals.iter() {
+            if let Condition::PriorityAbove { priority } = conditional.condition {
+                let highest = self.config.priority.highest_priority();
+                if priority >= highest {
+                    return Err(format!("Cards reach at most priority {}, \"{}\" can never trigger", highest, conditional.condition));
+                }
+            }
+            if matches!(conditional.outcome, Outcome::Bonus { .. }) && self.effects.first().is_none_or(|effect| effect.magnitude() == 0) {
+                return Err(format!("\"{}\" adds a bonus but the card's first effect has no magnitude", conditional.condition));
+            }
+        }
+        let gained: i32 = self.effects.iter().filter(|effect| self.config.mechanic(effect) == Some(Mechanic::GainBarnacles)).map(Effect::magnitude).sum();
+        if gained > 0 && gained >= self.barnacles {
+            return Err(format!("Card gains {} barnacles but only costs {}, it would pay for itself", gained, self.barnacles));
+        }
+        if trace.deduction == 0 || self.barnacles == 0 {
+            Err(format!(
                 "Card prio {} due to budget: {}",
                 self.priority, self.budget
-            )))
+            ))
         } else {
             Ok(self.clone())
         }
     }
 }
 
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Rarity, Effect, Cost, Recast Cost
+        write!(
+            f,
+            "{}: \n\t{}\n\t{}\n\t{}\n\t{}\n\t{}, {}",
+            self.name,
+            tr("card-priority", &[("priority", &self.priority)]),
+            tr("card-rarity", &[("rarity", &self.rarity.label())]),
+            tr("card-cast", &[("barnacles", &self.barnacles)]),
+            tr("card-withdraw", &[("barnacles", &self.get_withdraw())]),
+            effects_to_string(&self.effects, &self.config),
+            tr("card-range", &[("range", &self.config.describe_range(self.range.as_ref().unwrap()))])
+        )?;
+        if let Some(range) = self.range.as_ref() {
+            write!(f, "\n\t{}", tr("card-rules", &[("rules", &self.config.card_rules_text(&self.effects, range))]))?;
+        }
+        for conditional in self.conditionals.iter() {
+            write!(f, "\n\t{}", conditional.describe(&self.effects, &self.config))?;
+        }
+        if let Some(leftover) = self.leftover.as_ref() {
+            write!(f, "\n\t{}", tr("card-leftover", &[("leftover", leftover)]))?;
+        }
+        if let Some(flavour) = self.flavour.as_ref() {
+            write!(f, "\n\t\"{}\"", flavour)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn effects_to_string(effects: &[Effect], config: &Config) -> String {
+    let key = if effects.len() == 1 { "card-effect" } else { "card-effects" };
+    tr(key, &[("effects", &effects.iter().map(|effect| config.describe_effect(effect)).collect::<Vec<String>>().join(" + "))])
+}
+
 fn get_barnacles(card: &Card) -> i32 {
     // Formula = magnitude_of_effect * effect_type + range_modifier / efficiency
-    apply_multiplier(barnacles_from_effect(&card.effect) + cost_from_range(&card.range.as_ref().unwrap_or(&Range::Single)), 1.0 / multiplier_from_efficiency(&card.efficiency))
+    let (effects, conditionals, range) = barnacle_parts(card);
+    apply_multiplier(effects + conditionals + range, 1.0 / multiplier_from_efficiency(&card.efficiency, &card.config), card.config.rounding)
+}
+
+// Barnacles from the card's effects, conditional effects and range before efficiency applies
+fn barnacle_parts(card: &Card) -> (i32, i32, i32) {
+    let conditionals: i32 = card
+        .conditionals
+        .iter()
+        .filter_map(|conditional| {
+            let payload = conditional.payload(&card.effects)?;
+            Some(apply_multiplier(barnacles_from_effect(&payload, &card.config), card.config.condition_costs.get_multiplier(&conditional.condition), card.config.rounding))
+        })
+        .sum();
+    let range = card.range.as_ref().map_or(0, |range| apply_multiplier(cost_from_range(range, &card.config), card.config.barnacle_pricing.range_multiplier, card.config.rounding));
+    (barnacles_from_effects(&card.effects, &card.config), conditionals, range)
 }
 
-fn barnacles_from_effect(effect: &Option<Effect>) -> i32 {
-    if effect.as_ref().is_none() { return 100000000; }
-    match effect.as_ref().unwrap() {
-        Effect::Heal(magnitude) => apply_multiplier(*magnitude, 1.25),
-        Effect::AcidHeal(magnitude) => apply_multiplier(*magnitude, 1.125),
-        Effect::Damage(magnitude) => *magnitude,
-        Effect::Shield(magnitude) => apply_multiplier(*magnitude, 1.375),
+fn barnacles_from_effects(effects: &[Effect], config: &Config) -> i32 {
+    if effects.is_empty() { return 100000000; }
+    effects.iter().map(|effect| barnacles_from_effect(effect, config)).sum()
+}
+
+fn barnacles_from_effect(effect: &Effect, config: &Config) -> i32 {
+    let Some(definition) = config.definition(effect) else {
+        return 0;
+    };
+    let units = match definition.mechanic.kind() {
+        EffectKind::Control => effect.duration.unwrap_or(default_control_duration()),
+        _ => effect.total_magnitude(),
+    };
+    apply_multiplier(units, definition.barnacle_multiplier * config.barnacle_pricing.effect_multiplier, config.rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn build(input: &CardInput) -> Result<Card, String> {
+        input.build(&Config::default(), &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn budget_is_split_between_effects() {
+        assert_eq!(split_budget(7, 2), vec![4, 3]);
+        assert_eq!(split_budget(2, 3), vec![1, 1, 0]);
+        assert_eq!(split_budget(-1, 2), vec![0, 0]);
+    }
+
+    #[test]
+    fn cards_keep_every_effect_in_order() {
+        let mut input = CardInput::new(Rarity::Legendary);
+        input.effects = vec![Effect::new("Damage"), Effect::new("Heal")];
+        let card = build(&input).unwrap();
+        let ids: Vec<&str> = card.effects.iter().map(|effect| effect.id.as_str()).collect();
+        assert_eq!(ids, ["Damage", "Heal"]);
+        assert!(card.effects.iter().all(|effect| effect.magnitude > 0));
+    }
+
+    #[test]
+    fn over_time_effects_are_priced_per_tick() {
+        let config = Config::default();
+        let poison = Effect::new("Poison");
+        assert_eq!(poison.with_timing(5, 2).ticks(), 3);
+        assert_eq!(poison.with_timing(4, 1).with_magnitude(2).total_magnitude(), 8);
+        let magnitude = |duration: i32| cost_from_effect(poison.with_timing(duration, 2), 12, &Some(Range::new("Single")), &config).0.unwrap().magnitude();
+        assert_eq!(magnitude(2), 2 * magnitude(4));
+        let mut input = CardInput::new(Rarity::Rare);
+        input.effects = vec![poison];
+        let effect = build(&input).unwrap().effects[0].clone();
+        assert_eq!(effect.timing(), Some((default_duration(), default_tick())));
+        assert!(effect.magnitude > 0);
+    }
+
+    #[test]
+    fn control_effects_buy_turns_not_magnitude() {
+        let config = Config::default();
+        let mut input = CardInput::new(Rarity::Legendary);
+        input.effects = vec![Effect::new("Stun")];
+        let card = build(&input).unwrap();
+        assert_eq!((card.effects[0].magnitude, card.effects[0].duration), (0, Some(default_control_duration())));
+        assert!(card.barnacles >= config.min_control_barnacles);
+        input.effects = vec![Effect::new("Stun").with_duration(50)];
+        assert!(build(&input).unwrap_err().starts_with("Not enough budget"));
+    }
+
+    #[test]
+    fn efficiency_tiers_and_pricing_come_from_the_config() {
+        let mut config = Config::default();
+        config.efficiency.push(EfficiencyTier { id: String::from("Superb"), multiplier: 3.0 });
+        let mut input = CardInput::new(Rarity::Legendary);
+        let barnacles = |input: &CardInput, config: &Config| input.build(config, &mut StdRng::seed_from_u64(0)).unwrap().barnacles;
+        let bad = barnacles(&input, &config);
+        input.efficiency = Efficiency::new("superb");
+        let superb = barnacles(&input, &config);
+        assert!(superb < bad);
+        config.barnacle_pricing.effect_multiplier = 2.0;
+        assert!(barnacles(&input, &config) > superb);
+        config.barnacle_pricing.withdraw_fraction = 0.01;
+        config.barnacle_pricing.min_withdraw = 2;
+        assert_eq!(input.build(&config, &mut StdRng::seed_from_u64(0)).unwrap().get_withdraw(), 2);
+        input.efficiency = Efficiency::new("Perfect");
+        assert_eq!(build(&input).err(), Some(String::from("Unknown efficiency Perfect")));
+    }
+
+    #[test]
+    fn card_without_effects_does_not_build() {
+        let mut input = CardInput::new(Rarity::Rare);
+        input.effects.clear();
+        assert_eq!(build(&input).err(), Some(String::from("Card has no effects")));
+    }
+
+    #[test]
+    fn ledger_budget_follows_each_deduction() {
+        let config = Config::default();
+        let mut input = CardInput::new(Rarity::Rare);
+        input.range = Range::new("AoE");
+        let (ledger, result) = input.explain(&config, &mut StdRng::seed_from_u64(0));
+        let card = result.unwrap();
+        let budget = |step: &str| ledger.entries.iter().find(|entry| entry.step == step).map(|entry| entry.budget).unwrap();
+        assert_eq!(budget("Priority allocation"), budget("Power") - input.priority_allocation);
+        assert_eq!(budget("Range"), budget("Priority allocation") - cost_from_range(&input.range, &config));
+        assert_eq!(ledger.entries.last().unwrap().budget, card.budget);
     }
 }
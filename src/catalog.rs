@@ -0,0 +1,422 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, locale::*};
+
+// What an effect does when it resolves, catalog entries pick one of these and price it however they like
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Mechanic {
+    #[serde(alias = "damage")]
+    Damage,
+    #[serde(alias = "heal")]
+    Heal,
+    #[serde(alias = "acid_heal")]
+    AcidHeal,
+    #[serde(alias = "shield")]
+    Shield,
+    #[serde(alias = "poison")]
+    Poison,
+    #[serde(alias = "regeneration")]
+    Regeneration,
+    #[serde(alias = "timed_shield")]
+    TimedShield,
+    #[serde(alias = "stun")]
+    Stun,
+    #[serde(alias = "slow")]
+    Slow,
+    #[serde(alias = "taunt")]
+    Taunt,
+    #[serde(alias = "silence")]
+    Silence,
+    #[serde(alias = "gain_barnacles")]
+    GainBarnacles,
+    #[serde(alias = "drain_barnacles")]
+    DrainBarnacles,
+    #[serde(alias = "discount")]
+    Discount,
+    #[serde(alias = "draw")]
+    Draw,
+    #[serde(alias = "discard")]
+    Discard,
+    #[serde(alias = "return_to_hand")]
+    ReturnToHand,
+    #[serde(alias = "search")]
+    Search,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectKind {
+    // Magnitude bought from the budget and applied once
+    Instant,
+    // Magnitude applied when cast and again every `tick` turns until `duration` turns have passed
+    OverTime,
+    // No magnitude, a fixed cost per turn of `duration`
+    Control,
+}
+
+impl Mechanic {
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            Mechanic::Poison | Mechanic::Regeneration | Mechanic::TimedShield => EffectKind::OverTime,
+            Mechanic::Stun | Mechanic::Slow | Mechanic::Taunt | Mechanic::Silence => EffectKind::Control,
+            _ => EffectKind::Instant,
+        }
+    }
+
+    pub fn is_damage(&self) -> bool {
+        matches!(self, Mechanic::Damage | Mechanic::Poison)
+    }
+
+    // Effects aimed at the opposing side, acid healing also corrodes enemy shields
+    pub fn is_offensive(&self) -> bool {
+        matches!(
+            self,
+            Mechanic::Damage | Mechanic::AcidHeal | Mechanic::Poison | Mechanic::Stun | Mechanic::Slow | Mechanic::Silence | Mechanic::DrainBarnacles | Mechanic::Discard
+        )
+    }
+
+    // Barnacle and card flow effects act on the whole side rather than on party members
+    pub fn is_side_wide(&self) -> bool {
+        matches!(
+            self,
+            Mechanic::GainBarnacles | Mechanic::DrainBarnacles | Mechanic::Discount | Mechanic::Draw | Mechanic::Discard | Mechanic::ReturnToHand | Mechanic::Search
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct EffectDefinition {
+    // Written as the effect `type` in decks
+    pub id: String,
+    pub name: String,
+    pub mechanic: Mechanic,
+    // Budget per point of magnitude, or per turn for control effects
+    pub range_modifiers: RangeModifiers,
+    // Barnacles per point of magnitude, or per turn for control effects
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub barnacle_multiplier: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl EffectDefinition {
+    fn new(id: &str, name: &str, mechanic: Mechanic, range_modifiers: RangeModifiers, barnacle_multiplier: f32) -> Self {
+        EffectDefinition { id: String::from(id), name: String::from(name), mechanic, range_modifiers, barnacle_multiplier, icon: None }
+    }
+
+    pub fn matches(&self, id: &str) -> bool {
+        same_id(&self.id, id)
+    }
+
+    // Fills in the fields this kind of effect uses and drops the ones it does not
+    pub fn normalise(&self, mut effect: Effect) -> Effect {
+        effect.id = self.id.clone();
+        match self.mechanic.kind() {
+            EffectKind::Instant => (effect.duration, effect.tick) = (None, None),
+            EffectKind::OverTime => {
+                effect.duration = Some(effect.duration.unwrap_or(default_duration()));
+                effect.tick = Some(effect.tick.unwrap_or(default_tick()));
+            }
+            EffectKind::Control => {
+                effect.magnitude = 0;
+                effect.duration = Some(effect.duration.unwrap_or(default_control_duration()));
+                effect.tick = None;
+            }
+        }
+        effect.rarity = match self.mechanic {
+            Mechanic::Search => Some(effect.rarity.unwrap_or(default_search_rarity())),
+            _ => None,
+        };
+        effect
+    }
+
+    pub fn effect(&self) -> Effect {
+        self.normalise(Effect::new(&self.id))
+    }
+
+    pub fn label(&self) -> String {
+        match &self.icon {
+            Some(icon) => format!("{} {}", icon, tr_or(&effect_name_key(&self.id), &self.name, &[])),
+            None => tr_or(&effect_name_key(&self.id), &self.name, &[]),
+        }
+    }
+
+    pub fn describe(&self, effect: &Effect) -> String {
+        let name = self.label();
+        if let (Mechanic::Search, Some(rarity)) = (self.mechanic, &effect.rarity) {
+            return tr("effect-search", &[("name", &name), ("magnitude", &effect.magnitude), ("rarity", &rarity.label())]);
+        }
+        match (self.mechanic.kind(), effect.duration, effect.tick) {
+            (EffectKind::Control, Some(1), _) => tr("effect-control-turn", &[("name", &name), ("duration", &1)]),
+            (EffectKind::Control, Some(duration), _) => tr("effect-control-turns", &[("name", &name), ("duration", &duration)]),
+            (EffectKind::OverTime, Some(duration), Some(1)) => tr("effect-per-turn", &[("name", &name), ("magnitude", &effect.magnitude), ("duration", &duration)]),
+            (EffectKind::OverTime, Some(duration), Some(tick)) => {
+                tr("effect-every", &[("name", &name), ("magnitude", &effect.magnitude), ("tick", &tick), ("duration", &duration)])
+            }
+            _ => tr("effect-magnitude", &[("name", &name), ("magnitude", &effect.magnitude)]),
+        }
+    }
+}
+
+pub fn normalise_id(id: &str) -> String {
+    id.chars().filter(|ch| *ch != '_' && *ch != ' ').flat_map(char::to_lowercase).collect()
+}
+
+// `acid_heal`, `Acid Heal` and `AcidHeal` all name the same effect
+pub fn same_id(id: &str, other: &str) -> bool {
+    normalise_id(id) == normalise_id(other)
+}
+
+// Modifiers for the built-in Single, Multiple, AoE and ExtendedAoE ranges
+pub fn range_modifiers(single: f32, multiple: f32, aoe: f32, extended_aoe: f32) -> RangeModifiers {
+    let ids = ["Single", "Multiple", "AoE", "ExtendedAoE"];
+    RangeModifiers(ids.into_iter().map(String::from).zip([single, multiple, aoe, extended_aoe]).collect())
+}
+
+pub fn flat_range_modifiers(modifier: f32) -> RangeModifiers {
+    range_modifiers(modifier, modifier, modifier, modifier)
+}
+
+// Control effects cost more the more of the opposing party they reach
+pub fn control_range_modifiers(cost: f32) -> RangeModifiers {
+    range_modifiers(cost, cost * 1.5, cost * 2.0, cost * 2.5)
+}
+
+pub fn builtin_catalog() -> Vec<EffectDefinition> {
+    vec![
+        EffectDefinition::new("Damage", "Damage", Mechanic::Damage, range_modifiers(1.0, 1.25, 0.875, 0.75), 1.0),
+        EffectDefinition::new("Heal", "Heal", Mechanic::Heal, range_modifiers(1.5, 2.0, 1.25, 1.5), 1.25),
+        EffectDefinition::new("AcidHeal", "Acid Heal", Mechanic::AcidHeal, range_modifiers(1.25, 1.25, 1.75, 2.0), 1.125),
+        EffectDefinition::new("Shield", "Shield", Mechanic::Shield, range_modifiers(1.5, 2.0, 1.25, 1.5), 1.375),
+        EffectDefinition::new("Poison", "Poison", Mechanic::Poison, range_modifiers(0.75, 1.0, 0.625, 0.5), 1.0),
+        EffectDefinition::new("Regeneration", "Regeneration", Mechanic::Regeneration, range_modifiers(1.25, 1.5, 1.0, 1.25), 1.25),
+        EffectDefinition::new("TimedShield", "Timed Shield", Mechanic::TimedShield, range_modifiers(1.25, 1.5, 1.0, 1.25), 1.375),
+        EffectDefinition::new("Stun", "Stun", Mechanic::Stun, control_range_modifiers(4.0), 3.0),
+        EffectDefinition::new("Slow", "Slow", Mechanic::Slow, control_range_modifiers(2.0), 1.0),
+        EffectDefinition::new("Taunt", "Taunt", Mechanic::Taunt, control_range_modifiers(2.0), 2.0),
+        EffectDefinition::new("Silence", "Silence", Mechanic::Silence, control_range_modifiers(3.0), 2.0),
+        EffectDefinition::new("GainBarnacles", "Gain Barnacles", Mechanic::GainBarnacles, flat_range_modifiers(1.5), 1.25),
+        EffectDefinition::new("DrainBarnacles", "Drain Barnacles", Mechanic::DrainBarnacles, flat_range_modifiers(1.75), 1.125),
+        EffectDefinition::new("Discount", "Next Card Discount", Mechanic::Discount, flat_range_modifiers(1.25), 1.0),
+        EffectDefinition::new("Draw", "Draw", Mechanic::Draw, flat_range_modifiers(3.0), 2.0),
+        EffectDefinition::new("Discard", "Opponent Discards", Mechanic::Discard, flat_range_modifiers(3.5), 2.0),
+        EffectDefinition::new("ReturnToHand", "Return To Hand", Mechanic::ReturnToHand, flat_range_modifiers(2.5), 1.5),
+        EffectDefinition::new("Search", "Search", Mechanic::Search, flat_range_modifiers(4.0), 2.5),
+    ]
+}
+
+// Who a range reaches, counts are capped by the size of the party
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Targets {
+    // The weakest `count` members
+    #[serde(alias = "count")]
+    Count {
+        #[schemars(range(min = 1))]
+        count: usize,
+    },
+    // `count` members picked at random
+    #[serde(alias = "random")]
+    Random {
+        #[schemars(range(min = 1))]
+        count: usize,
+    },
+    // Everyone in the room, see `simulation.room_size`
+    #[serde(alias = "room")]
+    Room,
+    #[serde(alias = "party")]
+    Party,
+    // The whole casting side, effects aimed at enemies cannot use it
+    #[serde(alias = "allies")]
+    Allies,
+    // Only the caster, the first living member of the casting side, effects aimed at enemies hit the caster
+    #[serde(alias = "caster", alias = "self")]
+    Caster,
+    // `count` members side by side from the front of the party
+    #[serde(alias = "line")]
+    Line {
+        #[schemars(range(min = 1))]
+        count: usize,
+    },
+    // The weakest member and up to `spread` members on either side of it
+    #[serde(alias = "cone")]
+    Cone { spread: usize },
+}
+
+impl Targets {
+    // Ranges that never reach the opposing side
+    pub fn own_side_only(&self) -> bool {
+        matches!(self, Targets::Caster | Targets::Allies)
+    }
+}
+
+impl std::fmt::Display for Targets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Targets::Count { count } => write!(f, "{}", count),
+            Targets::Random { count } => write!(f, "{} random", count),
+            Targets::Room => write!(f, "room"),
+            Targets::Party => write!(f, "party"),
+            Targets::Allies => write!(f, "allies"),
+            Targets::Caster => write!(f, "caster"),
+            Targets::Line { count } => write!(f, "{} in a line", count),
+            Targets::Cone { spread } => write!(f, "cone spreading {}", spread),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct RangeDefinition {
+    // Written as the card's `range` in decks
+    pub id: String,
+    pub name: String,
+    // Budget taken from the card before its effects are priced, also added to its barnacle price
+    #[schemars(range(min = 0))]
+    pub cost: i32,
+    // Modifier for effects whose range_modifiers do not list this range, applied to their cheapest modifier
+    #[serde(default = "default_range_modifier")]
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub modifier: f32,
+    pub targets: Targets,
+}
+
+fn default_range_modifier() -> f32 {
+    1.0
+}
+
+impl RangeDefinition {
+    fn new(id: &str, name: &str, cost: i32, targets: Targets) -> Self {
+        RangeDefinition { id: String::from(id), name: String::from(name), cost, modifier: default_range_modifier(), targets }
+    }
+
+    fn with_modifier(mut self, modifier: f32) -> Self {
+        self.modifier = modifier;
+        self
+    }
+
+    pub fn matches(&self, id: &str) -> bool {
+        same_id(&self.id, id)
+    }
+
+    pub fn range(&self) -> Range {
+        Range::new(&self.id)
+    }
+}
+
+pub fn builtin_ranges() -> Vec<RangeDefinition> {
+    vec![
+        RangeDefinition::new("Single", "Single", 0, Targets::Count { count: 1 }),
+        RangeDefinition::new("Multiple", "Multiple", 1, Targets::Count { count: 2 }),
+        RangeDefinition::new("AoE", "AoE", 2, Targets::Room),
+        RangeDefinition::new("ExtendedAoE", "ExtendedAoE", 4, Targets::Party),
+        RangeDefinition::new("Self", "Self", 0, Targets::Caster).with_modifier(0.75),
+        RangeDefinition::new("Random", "Random", 1, Targets::Random { count: 3 }).with_modifier(0.875),
+        RangeDefinition::new("Line", "Line", 1, Targets::Line { count: 3 }).with_modifier(1.125),
+        RangeDefinition::new("Cone", "Cone", 2, Targets::Cone { spread: 1 }),
+        RangeDefinition::new("Allies", "All Allies", 3, Targets::Allies).with_modifier(1.25),
+    ]
+}
+
+impl Config {
+    pub fn efficiency_tier(&self, efficiency: &Efficiency) -> Option<&EfficiencyTier> {
+        self.efficiency.iter().find(|tier| tier.matches(&efficiency.id))
+    }
+
+    pub fn range_definition(&self, range: &Range) -> Option<&RangeDefinition> {
+        self.range_catalog.iter().find(|definition| definition.matches(&range.id))
+    }
+
+    // Ranges that only reach the casting side have no enemy to aim at
+    pub fn aimed_at_own_side(&self, effect: &Effect, range: &Range) -> bool {
+        self.mechanic(effect).is_some_and(|mechanic| mechanic.is_offensive()) && self.range_definition(range).is_some_and(|definition| definition.targets.own_side_only())
+    }
+
+    pub fn describe_range(&self, range: &Range) -> String {
+        self.range_definition(range).map_or(range.id.clone(), |definition| tr_or(&range_name_key(&definition.id), &definition.name, &[]))
+    }
+
+    pub fn definition(&self, effect: &Effect) -> Option<&EffectDefinition> {
+        self.effect_catalog.iter().find(|definition| definition.matches(&effect.id))
+    }
+
+    pub fn mechanic(&self, effect: &Effect) -> Option<Mechanic> {
+        self.definition(effect).map(|definition| definition.mechanic)
+    }
+
+    // Budget per point of magnitude at a range, per turn for control effects
+    pub fn effect_modifier(&self, effect: &Effect, range: &Range) -> Option<f32> {
+        let definition = self.definition(effect)?;
+        let range_modifier = definition.range_modifiers.get_modifier(self.range_definition(range)?);
+        match definition.mechanic.kind() {
+            EffectKind::Control => Some(range_modifier),
+            _ => Some(range_modifier * definition.normalise(effect.clone()).ticks() as f32),
+        }
+    }
+
+    pub fn describe_effect(&self, effect: &Effect) -> String {
+        match self.definition(effect) {
+            Some(definition) => definition.describe(effect),
+            None => tr("effect-magnitude", &[("name", &effect.id), ("magnitude", &effect.magnitude)]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::simulator::{MatchRules, Pattern};
+
+    fn build(input: &CardInput, config: &Config) -> Result<Card, String> {
+        input.build(config, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn ids_match_whatever_the_spelling() {
+        let config = Config::default();
+        assert!(config.effect_catalog.iter().any(|definition| definition.matches("acid_heal")));
+        assert_eq!(config.mechanic(&Effect::new("timed_shield")), Some(Mechanic::TimedShield));
+    }
+
+    #[test]
+    fn custom_effects_are_priced_from_the_catalog() {
+        let mut config = Config::default();
+        config.effect_catalog.push(EffectDefinition::new("Fireball", "Fireball", Mechanic::Damage, flat_range_modifiers(2.0), 1.5));
+        let mut input = CardInput::new(Rarity::Rare);
+        input.effects = vec![Effect::new("fireball")];
+        let card = build(&input, &config).unwrap();
+        assert_eq!(card.effects[0].id, "Fireball");
+        if let Some(fireball) = config.effect_catalog.last_mut() {
+            fireball.range_modifiers = flat_range_modifiers(4.0);
+        }
+        assert!(build(&input, &config).unwrap().effects[0].magnitude < card.effects[0].magnitude);
+        input.effects = vec![Effect::new("Meteor")];
+        assert_eq!(build(&input, &config).err(), Some(String::from("Unknown effect type Meteor")));
+    }
+
+    #[test]
+    fn unlisted_ranges_scale_the_cheapest_modifier() {
+        let mut config = Config::default();
+        let mut pair = RangeDefinition::new("Pair", "Pair", 1, Targets::Random { count: 2 });
+        pair.modifier = 2.0;
+        config.range_catalog.push(pair.clone());
+        let damage = config.definition(&Effect::new("Damage")).unwrap();
+        assert_eq!(damage.range_modifiers.get_modifier(&pair), 0.75 * 2.0);
+        let rules = MatchRules::default();
+        let reach = rules.reach(&Targets::Random { count: rules.party_size + 3 });
+        assert_eq!((reach.count, reach.pattern), (rules.party_size, Pattern::Random));
+    }
+
+    #[test]
+    fn ranges_take_their_cost_from_the_catalog() {
+        let mut config = Config::default();
+        config.range_catalog.push(RangeDefinition::new("Column", "Column", 3, Targets::Count { count: 3 }));
+        let mut input = CardInput::new(Rarity::Rare);
+        input.range = Range::new("Column");
+        assert_eq!(cost_from_range(&input.range, &config), 3);
+        assert!(build(&input, &config).is_ok());
+        input.range = Range::new("Spiral");
+        assert_eq!(build(&input, &config).err(), Some(String::from("Unknown range Spiral")));
+    }
+}
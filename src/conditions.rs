@@ -0,0 +1,151 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, locale::*, rounding::*};
+
+// When a conditional effect fires, cast conditions are checked as the card is cast
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Condition {
+    // The card the opponent casts the same turn has a priority above `priority`
+    #[serde(alias = "priority_above")]
+    PriorityAbove {
+        #[schemars(range(min = 0))]
+        priority: i32,
+    },
+    #[serde(alias = "target_shielded")]
+    TargetShielded,
+    #[serde(alias = "on_withdraw")]
+    OnWithdraw,
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::PriorityAbove { priority } => write!(f, "{}", tr("condition-priority-above", &[("priority", priority)])),
+            Condition::TargetShielded => write!(f, "{}", tr("condition-target-shielded", &[])),
+            Condition::OnWithdraw => write!(f, "{}", tr("condition-on-withdraw", &[])),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Outcome {
+    // Extra magnitude for the card's first effect
+    #[serde(alias = "bonus")]
+    Bonus {
+        #[serde(default)]
+        #[schemars(range(min = 0))]
+        magnitude: i32,
+    },
+    #[serde(alias = "extra")]
+    Extra { effect: Effect },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConditionalEffect {
+    pub condition: Condition,
+    pub outcome: Outcome,
+}
+
+impl ConditionalEffect {
+    // The effect resolved when the condition holds
+    pub fn payload(&self, effects: &[Effect]) -> Option<Effect> {
+        match &self.outcome {
+            Outcome::Bonus { magnitude } => Some(effects.first()?.with_magnitude(*magnitude)),
+            Outcome::Extra { effect } => Some(effect.clone()),
+        }
+    }
+
+    pub fn with_payload(&self, payload: &Effect) -> ConditionalEffect {
+        let outcome = match self.outcome {
+            Outcome::Bonus { .. } => Outcome::Bonus { magnitude: payload.magnitude() },
+            Outcome::Extra { .. } => Outcome::Extra { effect: payload.clone() },
+        };
+        ConditionalEffect { condition: self.condition.clone(), outcome }
+    }
+
+    pub fn describe(&self, effects: &[Effect], config: &Config) -> String {
+        match (&self.outcome, effects.first().and_then(|effect| config.definition(effect))) {
+            (Outcome::Bonus { magnitude }, Some(definition)) => format!("{}: +{} {}", self.condition, magnitude, definition.label()),
+            (Outcome::Bonus { magnitude }, None) => format!("{}: +{}", self.condition, magnitude),
+            (Outcome::Extra { effect }, _) => format!("{}: {}", self.condition, config.describe_effect(effect)),
+        }
+    }
+}
+
+// Multipliers on the budget and barnacle price of conditional payloads, below 1 for conditions that often fail
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct ConditionCosts {
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub priority_above: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub target_shielded: f32,
+    #[schemars(schema_with = "crate::schema::positive_number")]
+    pub on_withdraw: f32,
+}
+
+impl Default for ConditionCosts {
+    fn default() -> Self {
+        ConditionCosts { priority_above: 0.5, target_shielded: 0.5, on_withdraw: 0.75 }
+    }
+}
+
+impl ConditionCosts {
+    pub fn get_multiplier(&self, condition: &Condition) -> f32 {
+        match condition {
+            Condition::PriorityAbove { .. } => self.priority_above,
+            Condition::TargetShielded => self.target_shielded,
+            Condition::OnWithdraw => self.on_withdraw,
+        }
+    }
+}
+
+pub fn cost_from_conditional(conditional: &ConditionalEffect, budget: Fraction, effects: &[Effect], range: &Option<Range>, config: &Config) -> (Option<ConditionalEffect>, Fraction) {
+    let Some(payload) = conditional.payload(effects) else {
+        return (None, Fraction::zero());
+    };
+    let multiplier = config.condition_costs.get_multiplier(&conditional.condition);
+    let mut share = config.rounding.divide(budget, multiplier);
+    loop {
+        let (priced, used) = price_effect(payload.with_magnitude(0), share, range, config);
+        let charged = config.rounding.scale(used, multiplier);
+        // A share rounded up can cost more than the budget once the multiplier is applied back, shrink it until it fits
+        if charged <= budget || used <= Fraction::zero() {
+            return (priced.map(|payload| conditional.with_payload(&payload)), charged);
+        }
+        share = used - Fraction::from(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bonus(condition: Condition) -> ConditionalEffect {
+        ConditionalEffect { condition, outcome: Outcome::Bonus { magnitude: 0 } }
+    }
+
+    #[test]
+    fn bonuses_grow_the_first_effect() {
+        let effects = [Effect::new("Heal").with_magnitude(4), Effect::new("Damage")];
+        let payload = bonus(Condition::OnWithdraw).payload(&effects).unwrap();
+        assert_eq!((payload.id.as_str(), payload.magnitude), ("Heal", 0));
+        assert!(bonus(Condition::OnWithdraw).payload(&[]).is_none());
+    }
+
+    #[test]
+    fn less_likely_conditions_buy_more_magnitude() {
+        let config = Config::default();
+        let (effects, range) = ([Effect::new("Damage")], Some(Range::new("Single")));
+        let magnitude = |condition: Condition| {
+            let (priced, _) = cost_from_conditional(&bonus(condition), Fraction::from(8), &effects, &range, &config);
+            match priced.unwrap().outcome {
+                Outcome::Bonus { magnitude } => magnitude,
+                Outcome::Extra { .. } => unreachable!(),
+            }
+        };
+        assert!(magnitude(Condition::TargetShielded) > magnitude(Condition::OnWithdraw));
+    }
+}
@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{card::*, migrations::*};
+
+// Fields holding `{"type": ...}` tagged enums, these (and entries of `effects`) can also be written as just the variant name
+static SHORTHAND_FIELDS: [&str; 5] = ["rarity", "efficiency", "range", "effect", "condition"];
+
+pub static DECK_EXTENSIONS: [&str; 6] = ["deck", "json", "toml", "yaml", "yml", "ron"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+    Yaml,
+    Ron,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "deck" | "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "ron" => Some(Format::Ron),
+            _ => None,
+        }
+    }
+
+    pub fn parse(&self, contents: &str) -> Result<Value, String> {
+        match self {
+            Format::Json => serde_json::from_str(contents).map_err(|err| err.to_string()),
+            Format::Toml => toml::from_str(contents).map_err(|err| err.to_string()),
+            Format::Yaml => serde_yaml::from_str(contents).map_err(|err| err.to_string()),
+            Format::Ron => ron::from_str(contents).map_err(|err| err.to_string()),
+        }
+    }
+
+    pub fn write(&self, value: &Value) -> Result<String, String> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(value).map_err(|err| err.to_string()),
+            Format::Toml => toml::to_string_pretty(value).map_err(|err| err.to_string()),
+            Format::Yaml => serde_yaml::to_string(value).map_err(|err| err.to_string()),
+            Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+fn is_shorthand_field(key: &str) -> bool {
+    SHORTHAND_FIELDS.contains(&key)
+}
+
+fn expand_name(name: &str, is_effect: bool) -> Value {
+    let mut tagged = Map::new();
+    tagged.insert(String::from("type"), Value::String(String::from(name)));
+    if is_effect {
+        tagged.insert(String::from("magnitude"), Value::from(0));
+    }
+    Value::Object(tagged)
+}
+
+fn compact_name(tagged: &Map<String, Value>, is_effect: bool) -> Option<String> {
+    let zero_magnitude = is_effect && tagged.get("magnitude") == Some(&Value::from(0));
+    match tagged.get("type") {
+        Some(Value::String(name)) if tagged.len() == 1 || (tagged.len() == 2 && zero_magnitude) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+// `rarity = "rare"` -> `{"type": "rare"}`, effects without a magnitude start at 0
+pub fn expand_shorthand(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::String(name) if is_shorthand_field(key) => *field = expand_name(name, key == "effect"),
+                    Value::Array(effects) if key == "effects" => {
+                        for effect in effects.iter_mut() {
+                            match effect {
+                                Value::String(name) => *effect = expand_name(name, true),
+                                _ => expand_shorthand(effect),
+                            }
+                        }
+                    }
+                    _ => expand_shorthand(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(expand_shorthand),
+        _ => {}
+    }
+}
+
+// Inverse of expand_shorthand, only collapses values that expand back to exactly the same thing
+pub fn compact_shorthand(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::Object(tagged) if is_shorthand_field(key) => {
+                        if let Some(name) = compact_name(tagged, key == "effect") {
+                            *field = Value::String(name);
+                        }
+                    }
+                    Value::Array(effects) if key == "effects" => {
+                        for effect in effects.iter_mut() {
+                            match effect.as_object().and_then(|tagged| compact_name(tagged, true)) {
+                                Some(name) => *effect = Value::String(name),
+                                None => compact_shorthand(effect),
+                            }
+                        }
+                    }
+                    _ => compact_shorthand(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(compact_shorthand),
+        _ => {}
+    }
+}
+
+// Config multipliers are f32, print them as written rather than as their widened f64 value
+fn tidy_floats(value: &mut Value) {
+    match value {
+        Value::Number(number) if number.is_f64() => {
+            let tidy = number.as_f64().map(|float| (float as f32).to_string().parse::<f64>());
+            if let Some(Ok(float)) = tidy {
+                *value = Value::from(float);
+            }
+        }
+        Value::Object(map) => map.values_mut().for_each(tidy_floats),
+        Value::Array(items) => items.iter_mut().for_each(tidy_floats),
+        _ => {}
+    }
+}
+
+fn format_of(path: &Path) -> Result<Format, String> {
+    Format::from_path(path).ok_or(format!("Unknown file format: {}", path.display()))
+}
+
+pub fn read_value(path: &Path) -> Result<Value, String> {
+    let format = format_of(path)?;
+    let contents = std::fs::read_to_string(path).map_err(|_| format!("Could not read file: {}", path.display()))?;
+    let mut value = format.parse(contents.as_str())?;
+    expand_shorthand(&mut value);
+    Ok(value)
+}
+
+pub fn parse_document<T: Versioned>(format: Format, contents: &str) -> Result<T, String> {
+    let mut value = format.parse(contents)?;
+    expand_shorthand(&mut value);
+    migrate::<T>(&mut value)?;
+    serde_json::from_value(value).map_err(|err| err.to_string())
+}
+
+pub fn read_document<T: Versioned>(path: &Path) -> Result<T, String> {
+    let mut value = read_value(path)?;
+    migrate::<T>(&mut value)?;
+    serde_json::from_value(value).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+pub fn write_document<T: Serialize>(path: &Path, document: &T) -> Result<(), String> {
+    let format = format_of(path)?;
+    let mut value = serde_json::to_value(document).map_err(|err| err.to_string())?;
+    tidy_floats(&mut value);
+    if format != Format::Json {
+        compact_shorthand(&mut value);
+    }
+    let mut contents = format.write(&value)?;
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    std::fs::write(path, contents).map_err(|_| format!("Could not write to file: {}", path.display()))
+}
+
+pub fn convert_document(input: Option<String>, output: Option<String>) {
+    let (Some(input), Some(output)) = (input, output) else {
+        println!("Usage: --convert <input file> <output file> (format chosen by extension: .deck/.json, .toml, .yaml/.yml, .ron)");
+        return;
+    };
+    let (input, output) = (Path::new(&input), Path::new(&output));
+    let result = read_value(input).and_then(|value| {
+        if value.get("inputs").is_some() {
+            write_document(output, &read_document::<DeckInputs>(input)?)
+        } else {
+            write_document(output, &read_document::<Config>(input)?)
+        }
+    });
+    match result {
+        Ok(()) => println!("Converted {} to {}", input.display(), output.display()),
+        Err(err) => println!("Could not convert {}: {}", input.display(), err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What write_document would put in a file of the given format
+    fn written<T: Serialize>(document: &T, format: Format) -> String {
+        let mut value = serde_json::to_value(document).unwrap();
+        tidy_floats(&mut value);
+        if format != Format::Json {
+            compact_shorthand(&mut value);
+        }
+        format.write(&value).unwrap()
+    }
+
+    #[test]
+    fn formats_come_from_the_file_extension() {
+        let format = |path: &str| Format::from_path(Path::new(path));
+        assert_eq!(format("decks/a/a.deck"), Some(Format::Json));
+        assert_eq!(format("config.toml"), Some(Format::Toml));
+        assert_eq!(format("a.yml"), Some(Format::Yaml));
+        assert_eq!(format("a.ron"), Some(Format::Ron));
+        assert_eq!(format("a.txt"), None);
+    }
+
+    #[test]
+    fn default_config_round_trips_in_every_format() {
+        let config = Config::default();
+        for format in [Format::Json, Format::Toml, Format::Yaml, Format::Ron] {
+            let contents = written(&config, format);
+            let read: Config = parse_document(format, &contents).unwrap_or_else(|err| panic!("{:?}: {}", format, err));
+            assert_eq!(written(&read, format), contents, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn deck_round_trips_in_every_format() {
+        let deck = DeckInputs::new(DeckType::Journeyman);
+        for format in [Format::Json, Format::Toml, Format::Yaml, Format::Ron] {
+            let contents = written(&deck, format);
+            let read: DeckInputs = parse_document(format, &contents).unwrap_or_else(|err| panic!("{:?}: {}", format, err));
+            assert_eq!(written(&read, format), contents, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn shorthand_expands_and_compacts() {
+        let mut value = serde_json::json!({ "rarity": "rare", "effects": ["Damage", { "type": "Heal", "magnitude": 2 }] });
+        expand_shorthand(&mut value);
+        assert_eq!(value, serde_json::json!({ "rarity": { "type": "rare" }, "effects": [{ "type": "Damage", "magnitude": 0 }, { "type": "Heal", "magnitude": 2 }] }));
+        compact_shorthand(&mut value);
+        assert_eq!(value, serde_json::json!({ "rarity": "rare", "effects": ["Damage", { "type": "Heal", "magnitude": 2 }] }));
+    }
+
+    #[test]
+    fn hand_written_toml_deck_reads_like_json() {
+        let card = "[[inputs]]\nname = \"Zap\"\nrarity = \"Rare\"\nefficiency = \"Good\"\npriority_allocation = 2\nrange = \"AoE\"\neffects = [\"Damage\"]\n";
+        let toml = format!("version = 2\n{}", card.repeat(5));
+        let from_toml: DeckInputs = parse_document(Format::Toml, &toml).unwrap();
+        let card = r#"{ "name": "Zap", "rarity": { "type": "Rare" }, "efficiency": { "type": "Good" }, "priority_allocation": 2, "range": { "type": "AoE" }, "effects": [{ "type": "Damage", "magnitude": 0 }] }"#;
+        let json = format!(r#"{{ "version": 2, "inputs": [{}] }}"#, [card; 5].join(", "));
+        let from_json: DeckInputs = parse_document(Format::Json, &json).unwrap();
+        assert_eq!(serde_json::to_value(from_toml).unwrap(), serde_json::to_value(from_json).unwrap());
+    }
+}
@@ -1,28 +1,38 @@
-use std::ffi::OsStr;
+use std::{ffi::{OsStr, OsString}, path::{Path, PathBuf}};
 
-use crate::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{*, autofill::*, formats::*, locale::*, names::*, retier::*, rounding::*, simulator::*, themes::*, tuning::*};
 
 pub fn generate_cards(config: Config) {
+    let mut names = NameGenerator::new(&config);
     loop {
         let name = get_name();
         if name.is_empty() {
             break;
         }
+        if name != "?" {
+            names.take(&name);
+        }
         let rarity = get_rarity();
-        let efficiency = get_efficiency();
+        let efficiency = get_efficiency(&config);
         let mut card = Card::new(name, rarity, efficiency, config.clone());
         card.print_budget_mut();
         card.with_priority_allocation(get_priority_allocation(&card));
         card.print_budget_mut();
-        card.with_range(get_range());
+        card.with_range(get_range(&config));
         card.print_budget_mut();
-        card.with_effect(get_effect(&card));
+        card.with_effects(get_effects(&card));
+        if card.name == "?" {
+            card.name = names.name(&card.rarity, card.range.as_ref(), &card.effects, &mut rand::thread_rng());
+        }
+        card.with_flavour(get_flavour());
         let card_result = card.build();
 
         match card_result {
             Ok(card) => {
                 let card_str = card.to_string();
-                println!("\nGenerated Card:\n{}", card.to_string());
+                println!("\n{}\n{}", tr("card-generated", &[]), card);
                 let Ok(mut card_file) = OpenOptions::new()
                     .write(true)
                     .create(true)
@@ -34,7 +44,7 @@ pub fn generate_cards(config: Config) {
                 };
                 let write_result = card_file.write_all(card_str.as_bytes());
                 if write_result.is_ok() {
-                    println!("Wrote card to file: {}.card", card.name);
+                    println!("{}", tr("card-written", &[("file", &format!("{}.card", card.name))]));
                 }
             }
             Err(err) => eprintln!("ERROR: {}", err),
@@ -47,7 +57,6 @@ pub fn generate_deck_from_template(deck_name_opt: Option<String>, config: Config
         println!("No deck name provided - must match folder name containing .deck file...");
         return;
     };
-    let mut options = OpenOptions::new();
     let deck_folder = format!("decks/{}/", deck_name);
     let Ok(cards) = std::fs::read_dir(deck_folder.as_str()) else {
         println!("Could not read directory {}!", deck_folder);
@@ -56,7 +65,7 @@ pub fn generate_deck_from_template(deck_name_opt: Option<String>, config: Config
     for card in cards
         .filter_map(|res| res.ok())
         .map(|dir| dir.path())
-        .filter(|path| path.extension().unwrap_or(&OsStr::new("")) == "card")
+        .filter(|path| path.extension().unwrap_or(OsStr::new("")) == "card")
     {
         let path = card.clone().to_str().unwrap_or("Unknown").to_string();
         if std::fs::remove_file(card).is_err() {
@@ -64,35 +73,28 @@ pub fn generate_deck_from_template(deck_name_opt: Option<String>, config: Config
             return;
         }
     }
-    let deck_file = format!("{}{}.deck", deck_folder, deck_name);
-    let Ok(mut deck_file) = options.read(true).open(deck_file) else {
-        println!("No deck file present in {}", deck_folder);
-        return;
-    };
-    let mut deck_buf = String::new();
-    if deck_file.read_to_string(&mut deck_buf).is_err() {
-        println!("Could not read file: {:?}", deck_file);
-        return;
-    }
-    let Ok(deck) = serde_json::from_str::<DeckInputs>(deck_buf.as_str()) else {
-        println!("Could not parse deck!");
-        return;
+    let deck = match load_deck(&deck_name) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
     };
+    write_cards(&deck_folder, deck, &config, &mut rand::thread_rng());
+}
+
+fn write_cards<R: Rng + ?Sized>(deck_folder: &str, deck: DeckInputs, config: &Config, rng: &mut R) {
+    let mut options = OpenOptions::new();
     for card_input in deck.inputs {
-        let card_result = Card::new(
-            card_input.name.clone(),
-            card_input.rarity,
-            card_input.efficiency,
-            config.clone(),
-        )
-        .with_priority_allocation(card_input.priority_allocation)
-        .with_range(card_input.range)
-        .with_effect(card_input.effect)
-        .build();
+        let card_result = card_input.build(config, rng);
+        if let Err(err) = &card_result {
+            println!("Could not build card {}: {}", card_input.name, err);
+        }
         if let Ok(card) = card_result {
             let Ok(mut card_file) = options
                 .write(true)
                 .create(true)
+                .truncate(true)
                 .open(format!("{}{}.card", deck_folder, card_input.name))
             else {
                 println!("Could not create file: {}", card_input.name);
@@ -105,11 +107,474 @@ pub fn generate_deck_from_template(deck_name_opt: Option<String>, config: Config
     }
 }
 
+pub fn deck_path(deck_name: &str) -> Result<PathBuf, String> {
+    let deck_folder = format!("decks/{}/", deck_name);
+    DECK_EXTENSIONS
+        .iter()
+        .map(|extension| PathBuf::from(format!("{}{}.{}", deck_folder, deck_name, extension)))
+        .find(|path| path.exists())
+        .ok_or(format!("No deck file present in {}", deck_folder))
+}
+
+pub fn load_deck(deck_name: &str) -> Result<DeckInputs, String> {
+    read_document(&deck_path(deck_name)?).map_err(|err| format!("Could not parse deck! {}", err))
+}
+
+pub fn simulate_decks(args: Vec<String>, config: Config) {
+    let (Some(deck_name), Some(opponent_name)) = (args.first(), args.get(1)) else {
+        println!("Usage: --simulate <deck> <opponent deck> [games per matchup] [seed]");
+        return;
+    };
+    let games = args.get(2).and_then(|games| games.parse().ok()).unwrap_or(100);
+    let seed = args.get(3).and_then(|seed| seed.parse().ok()).unwrap_or(0);
+    let decks = match (load_deck(deck_name), load_deck(opponent_name)) {
+        (Ok(deck), Ok(opponent)) => (deck, opponent),
+        (Err(err), _) | (_, Err(err)) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let reports = match round_robin([&decks.0, &decks.1], &config, games, seed) {
+        Ok(reports) => reports,
+        Err(err) => {
+            println!("Could not build deck: {}", err);
+            return;
+        }
+    };
+    println!("{} vs {} ({} games per matchup, seed {})", deck_name, opponent_name, games, seed);
+    println!("{}{}Win / Loss / Draw", pad_right(String::from("Strategy"), PADDING, ' '), pad_right(String::from("Opponent strategy"), PADDING, ' '));
+    for report in reports.iter() {
+        println!(
+            "{}{}{} / {} / {} ({:.1}%)",
+            pad_right(report.strategy.clone(), PADDING, ' '),
+            pad_right(report.opponent_strategy.clone(), PADDING, ' '),
+            report.wins,
+            report.losses,
+            report.draws,
+            report.win_rate() * 100.0
+        );
+    }
+    let overall = reports.iter().map(|report| report.win_rate()).sum::<f32>() / reports.len().max(1) as f32;
+    println!("Overall win rate for {}: {:.1}%", deck_name, overall * 100.0);
+}
+
+pub fn tune_config(args: Vec<String>, config: Config) {
+    let mut objectives = Vec::new();
+    let mut options = TuningOptions::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "win-rate" => {
+                let (Some(deck_name), Some(opponent_name), Some(Ok(target))) = (args.next(), args.next(), args.next().map(|target| target.parse::<f32>())) else {
+                    println!("Usage: win-rate <deck> <opponent deck> <target win rate (0..1)>");
+                    return;
+                };
+                match (load_deck(&deck_name), load_deck(&opponent_name)) {
+                    (Ok(deck), Ok(opponent)) => objectives.push(Objective::WinRate { deck_name, deck: Box::new(deck), opponent_name, opponent: Box::new(opponent), target }),
+                    (Err(err), _) | (_, Err(err)) => {
+                        println!("{}", err);
+                        return;
+                    }
+                }
+            }
+            "parity" => {
+                let Some(Ok(tolerance)) = args.next().map(|tolerance| tolerance.parse::<f32>()) else {
+                    println!("Usage: parity <tolerance (0..1)>");
+                    return;
+                };
+                objectives.push(Objective::HealDamageParity { tolerance });
+            }
+            "--iterations" => options.iterations = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.iterations),
+            "--games" => options.games = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.games),
+            "--seed" => options.seed = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.seed),
+            _ => {
+                println!("Unknown tuning argument: {}", arg);
+                return;
+            }
+        }
+    }
+    if objectives.is_empty() {
+        println!("Usage: --tune [win-rate <deck> <opponent deck> <target>] [parity <tolerance>] [--iterations N] [--games N] [--seed N]");
+        return;
+    }
+    let result = match tune(&config, &objectives, &options) {
+        Ok(result) => result,
+        Err(err) => {
+            println!("Could not tune config: {}", err);
+            return;
+        }
+    };
+    let report = tuning_report(&config, &objectives, &options, &result);
+    println!("{}", report);
+    let Ok(config_buf) = serde_json::to_string_pretty(&result.config) else {
+        println!("Bad type!");
+        return;
+    };
+    for (path, contents) in [(TUNED_CONFIG_PATH, config_buf), (TUNING_REPORT_PATH, report)] {
+        if std::fs::write(path, contents).is_err() {
+            println!("Could not write to file: {}", path);
+            return;
+        }
+    }
+    println!("Wrote candidate config to {} and report to {}", TUNED_CONFIG_PATH, TUNING_REPORT_PATH);
+}
+
+pub fn check_config(config: Config) -> i32 {
+    let report = config.validate();
+    report.print();
+    println!("{}: {} error(s), {} warning(s)", crate::PATH, report.errors.len(), report.warnings.len());
+    if report.is_valid() { 0 } else { 1 }
+}
+
+pub fn check_deck(deck_name: Option<String>, config: Config) -> i32 {
+    let Some(deck_name) = deck_name else {
+        println!("Usage: --deck-check <deck>");
+        return 1;
+    };
+    let deck = match load_deck(&deck_name) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return 1;
+        }
+    };
+    let report = deck.validate(&config);
+    report.print();
+    println!("{}: {} error(s), {} warning(s)", deck_name, report.errors.len(), report.warnings.len());
+    if report.is_valid() { 0 } else { 1 }
+}
+
+// Prints the missing keys as lines that can be pasted into the locale's file and translated
+pub fn list_missing_translations(locale: Option<String>, config: Config) -> i32 {
+    let Some(locale) = locale else {
+        println!("Usage: --locale-missing <locale>");
+        return 1;
+    };
+    let catalog = match Catalog::load(&locale) {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            println!("{}", err);
+            return 1;
+        }
+    };
+    let (missing, unknown) = missing_keys(&catalog, &config);
+    for key in unknown.iter() {
+        println!("WARNING: {} is not a message key", key);
+    }
+    if !missing.is_empty() {
+        println!("# Missing from {}{}.ftl", LOCALES_FOLDER, locale);
+    }
+    for (key, text) in missing.iter() {
+        println!("{} = {}", key, text.replace('\n', "\\n"));
+    }
+    println!("{}: {} missing key(s), {} unknown key(s)", locale, missing.len(), unknown.len());
+    if missing.is_empty() { 0 } else { 1 }
+}
+
+pub fn explain_priority(args: Vec<String>, config: Config) {
+    let usage = "Usage: --priority-explain <rarity> <priority allocation>";
+    let (Some(rarity), Some(allocation)) = (args.first(), args.get(1)) else {
+        println!("{}", usage);
+        return;
+    };
+    let Ok(rarity) = serde_json::from_value::<Rarity>(serde_json::json!({ "type": rarity })) else {
+        println!("Unknown rarity {}", rarity);
+        return;
+    };
+    let Ok(allocation) = allocation.parse::<i32>() else {
+        println!("{}", usage);
+        return;
+    };
+    println!("{}\n{}", tr("priority-explain", &[("rarity", &rarity.label()), ("allocation", &allocation)]), config.priority_trace(allocation, &rarity));
+}
+
+// Prints the ledger of every card in a deck, or of one card, as text or JSON
+pub fn explain_cards(args: Vec<String>, config: Config) {
+    let json = args.iter().any(|arg| arg == "--json");
+    let mut args = args.into_iter().filter(|arg| arg != "--json");
+    let Some(deck_name) = args.next() else {
+        println!("Usage: --card-explain <deck> [card] [--json]");
+        return;
+    };
+    let card_name = args.next();
+    let deck = match load_deck(&deck_name) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let inputs: Vec<&CardInput> = deck.inputs.iter().filter(|input| card_name.as_ref().is_none_or(|name| input.name == *name)).collect();
+    if inputs.is_empty() {
+        println!("No card named {} in {}", card_name.unwrap_or_default(), deck_name);
+        return;
+    }
+    let mut explanations = Vec::new();
+    for input in inputs {
+        let (ledger, result) = input.explain(&config, &mut rand::thread_rng());
+        if json {
+            let (card, rules, error) = match result {
+                Ok(card) => (Some(card.to_string()), Some(config.card_rules_text(&card.effects, &input.range)), None),
+                Err(err) => (None, None, Some(err)),
+            };
+            explanations.push(serde_json::json!({ "name": input.name, "ledger": ledger, "card": card, "rules": rules, "flavour": input.flavour, "error": error }));
+            continue;
+        }
+        print!("{}:\n{}", input.name, ledger);
+        match result {
+            Ok(card) => println!("{}\n", card),
+            Err(err) => println!("Could not build card: {}\n", err),
+        }
+    }
+    if json {
+        match serde_json::to_string_pretty(&explanations) {
+            Ok(buf) => println!("{}", buf),
+            Err(_) => println!("Bad type!"),
+        }
+    }
+}
+
+// How much of each card's budget is lost to rounding, under the config's rounding policy or the one given
+pub fn rounding_report(args: Vec<String>, mut config: Config) {
+    let Some(deck_name) = args.first() else {
+        println!("Usage: --rounding-report <deck> [floor|half_even|ceil|exact]");
+        return;
+    };
+    if let Some(policy) = args.get(1) {
+        match serde_json::from_value::<RoundingPolicy>(serde_json::json!(policy)) {
+            Ok(policy) => config.rounding = policy,
+            Err(_) => {
+                println!("Unknown rounding policy {}", policy);
+                return;
+            }
+        }
+    }
+    let deck = match load_deck(deck_name) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    println!("{}", tr("rounding-report", &[("deck", &deck_name), ("policy", &config.rounding.label())]));
+    let mut total = RoundingLoss::default();
+    for input in deck.inputs.iter() {
+        let card = match input.build(&config, &mut StdRng::seed_from_u64(0)) {
+            Ok(card) => card,
+            Err(err) => {
+                println!("{}: could not build card: {}", input.name, err);
+                continue;
+            }
+        };
+        let loss = card.rounding_loss;
+        println!(
+            "{}: {:.2} / {:.2} / {:.2} / {:.2} / {:.2}",
+            input.name, loss.available.to_f32(), loss.exact.to_f32(), loss.charged.to_f32(), loss.lost().to_f32(), loss.mispriced().to_f32()
+        );
+        total = RoundingLoss { available: total.available + loss.available, exact: total.exact + loss.exact, charged: total.charged + loss.charged };
+    }
+    println!("Total: {:.2} of {:.2} budget lost, effects mispriced by {:.2}", total.lost().to_f32(), total.available.to_f32(), total.mispriced().to_f32());
+}
+
+// Proposes a deck's cards, or one card, at another rarity, and writes the proposal back to the deck with --write
+pub fn retier_deck(args: Vec<String>, config: Config) {
+    let write = args.iter().any(|arg| arg == "--write");
+    let mut args = args.into_iter().filter(|arg| arg != "--write");
+    let (Some(deck_name), Some(target)) = (args.next(), args.next()) else {
+        println!("Usage: --retier <deck> <rarity|up|down> [card] [--write]");
+        return;
+    };
+    let card_name = args.next();
+    let (path, mut deck) = match deck_path(&deck_name).and_then(|path| Ok((path.clone(), load_deck(&deck_name)?))) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let shift = match target.as_str() {
+        "up" => Some(1),
+        "down" => Some(-1),
+        _ => None,
+    };
+    let rarity = serde_json::from_value::<Rarity>(serde_json::json!({ "type": target })).ok();
+    if shift.is_none() && rarity.is_none() {
+        println!("Unknown rarity {}", target);
+        return;
+    }
+    let mut retiered = 0;
+    for input in deck.inputs.iter_mut().filter(|input| card_name.as_ref().is_none_or(|name| input.name == *name)) {
+        let Some(rarity) = rarity.clone().or_else(|| input.rarity.shifted(shift.unwrap_or(0))) else {
+            println!("{}\n", tr("retier-already", &[("card", &input.name), ("rarity", &input.rarity.label())]));
+            continue;
+        };
+        let proposal = retier(input, rarity, &config);
+        println!("{}\n", compare_cards(input, &proposal, &config));
+        *input = proposal;
+        retiered += 1;
+    }
+    if retiered == 0 {
+        match card_name {
+            Some(name) if !deck.inputs.iter().any(|input| input.name == name) => println!("No card named {} in {}", name, deck_name),
+            _ => println!("No card to retier in {}", deck_name),
+        }
+        return;
+    }
+    if !write {
+        println!("Run again with --write to save the proposal to {}", path.display());
+        return;
+    }
+    match save_deck(&path, &deck) {
+        Ok(backup) => println!("Wrote {} card(s) to {}, the original is kept as {:?}", retiered, path.display(), backup),
+        Err(err) => println!("{}", err),
+    }
+}
+
+// Overwrites a deck file, keeping the original next to it as <file>.bak
+fn save_deck(path: &Path, deck: &DeckInputs) -> Result<OsString, String> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    std::fs::copy(path, &backup).map_err(|_| format!("Could not back up file to {:?}", backup))?;
+    write_document(path, deck)?;
+    Ok(backup)
+}
+
+pub fn autofill_deck(args: Vec<String>, config: Config) {
+    let usage = "Usage: --autofill <deck> [min-effect <effect> <count>] [max-range <range> <count>] [priority <average>] [balanced] [--iterations N] [--seed N] [--write]";
+    let mut args = args.into_iter();
+    let Some(deck_name) = args.next() else {
+        println!("{}", usage);
+        return;
+    };
+    let mut goals = Vec::new();
+    let mut options = AutofillOptions::default();
+    let mut write = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "min-effect" => {
+                let (Some(effect), Some(Ok(count))) = (args.next(), args.next().map(|count| count.parse::<usize>())) else {
+                    println!("Usage: min-effect <effect> <count>");
+                    return;
+                };
+                if config.definition(&Effect::new(&effect)).is_none() {
+                    println!("Unknown effect type {}", effect);
+                    return;
+                }
+                goals.push(Goal::MinEffect { effect, count });
+            }
+            "max-range" => {
+                let (Some(range), Some(Ok(count))) = (args.next(), args.next().map(|count| count.parse::<usize>())) else {
+                    println!("Usage: max-range <range> <count>");
+                    return;
+                };
+                if config.range_definition(&Range::new(&range)).is_none() {
+                    println!("Unknown range {}", range);
+                    return;
+                }
+                goals.push(Goal::MaxRange { range, count });
+            }
+            "priority" => {
+                let Some(Ok(target)) = args.next().map(|target| target.parse::<f32>()) else {
+                    println!("Usage: priority <average priority>");
+                    return;
+                };
+                goals.push(Goal::AveragePriority { target });
+            }
+            "balanced" => goals.push(Goal::Balanced),
+            "--iterations" => options.iterations = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.iterations),
+            "--seed" => options.seed = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.seed),
+            "--write" => write = true,
+            _ => {
+                println!("Unknown autofill argument: {}", arg);
+                return;
+            }
+        }
+    }
+    let (path, deck) = match deck_path(&deck_name).and_then(|path| Ok((path.clone(), load_deck(&deck_name)?))) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let result = autofill(&deck, &goals, &config, &options);
+    println!("{}", autofill_report(&deck, &goals, &options, &result, &config));
+    if !write {
+        println!("Run again with --write to save the filled deck to {}", path.display());
+        return;
+    }
+    match save_deck(&path, &result.deck) {
+        Ok(backup) => println!("Wrote {}, the original is kept as {:?}", path.display(), backup),
+        Err(err) => println!("{}", err),
+    }
+}
+
+// Writes a deck filled with picks from a theme, then builds its cards, the same seed always gives the same deck
+pub fn generate_random_deck(args: Vec<String>, config: Config) {
+    let usage = "Usage: --deck-random [--archetype starter|journeyman|legendary] [--theme <theme>] [--seed N] [--name <deck>]";
+    let (mut deck_type, mut theme_id, mut seed, mut deck_name) = (DeckType::Starter, String::from("Balanced"), rand::thread_rng().gen::<u64>(), None);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--archetype", Some(archetype)) => {
+                deck_type = match archetype.to_lowercase().as_str() {
+                    "starter" => DeckType::Starter,
+                    "journeyman" => DeckType::Journeyman,
+                    "legendary" => DeckType::Legendary,
+                    _ => {
+                        println!("Unknown archetype {}", archetype);
+                        return;
+                    }
+                }
+            }
+            ("--theme", Some(theme)) => theme_id = theme,
+            ("--seed", Some(value)) => {
+                let Ok(value) = value.parse() else {
+                    println!("{}", usage);
+                    return;
+                };
+                seed = value;
+            }
+            ("--name", Some(name)) => deck_name = Some(name),
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+    let Some(theme) = config.theme(&theme_id) else {
+        println!("Unknown theme {}, the config has: {}", theme_id, config.themes.iter().map(|theme| theme.id.as_str()).collect::<Vec<&str>>().join(", "));
+        return;
+    };
+    let deck_name = deck_name.unwrap_or(format!("{}_{}", theme.id.to_lowercase(), seed));
+    if deck_path(&deck_name).is_ok() {
+        println!("decks/{}/ already has a deck file, pick another --name", deck_name);
+        return;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let deck = match random_deck(deck_type.clone(), theme, &config, &mut rng) {
+        Ok(deck) => deck,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let deck_folder = format!("decks/{}/", deck_name);
+    let path = PathBuf::from(format!("{}{}.deck", deck_folder, deck_name));
+    if let Err(err) = std::fs::create_dir_all(&deck_folder).map_err(|_| format!("Could not create directory {}", deck_folder)).and_then(|_| write_document(&path, &deck)) {
+        println!("{}", err);
+        return;
+    }
+    println!("{}", tr("deck-random-written", &[("deck_type", &deck_type.label()), ("theme", &theme.id), ("file", &path.display()), ("seed", &seed)]));
+    deck.validate(&config).print();
+    write_cards(&deck_folder, deck, &config, &mut rng);
+}
+
 pub fn generate_deck_file() {
     let deck_type = match get_num(
         1,
         3,
-        String::from("1: Starter\n2: Journeyman\n3: Legendary\nEnter deck type (1..3)... "),
+        format!("{} ", tr("prompt-deck-type", &[])),
     ) - 1
     {
         2 => DeckType::Legendary,
@@ -119,6 +584,7 @@ pub fn generate_deck_file() {
     let mut file = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(true)
         .open(format!("{:?} Template.json", deck_type).as_str())
         .expect("Could not create template");
     file.set_len(0).expect("File could not be modified!");
@@ -137,7 +603,7 @@ pub fn generate_deck() {
     let deck_type = match get_num(
         1,
         3,
-        String::from("1: Starter\n2: Journeyman\n3: Legendary\nEnter deck type (1..3)... "),
+        format!("{} ", tr("prompt-deck-type", &[])),
     ) - 1
     {
         2 => DeckType::Legendary,
@@ -145,13 +611,14 @@ pub fn generate_deck() {
         _ => DeckType::Starter,
     };
 
-    let deck_name = get_string(String::from("Enter deck name: "));
+    let deck_name = get_string(format!("{} ", tr("prompt-deck-name", &[])));
     let mut deck = DeckInputs::new(deck_type);
     let root_path = format!("decks/{}/", deck_name);
     let mut last_card: Option<(Rarity, i32)> = Option::None;
 
+    let mut names = NameGenerator::new(&config);
     for card_input in deck.inputs.iter_mut() {
-        let card = configure_card(card_input, &config, &mut last_card);
+        let card = configure_card(card_input, &config, &mut last_card, &mut names);
         card_input.apply_configuration(&card);
     }
 
@@ -178,7 +645,7 @@ pub fn generate_deck() {
         return;
     }
     generate_deck_from_template(Some(deck_name.clone()), config);
-    println!("Generated deck: {}", deck_name.clone());
+    println!("{}", tr("deck-generated", &[("deck", &deck_name)]));
 }
 
 fn get_card_suffix(last_card: &mut Option<(Rarity, i32)>, current_rarity: &Rarity) -> String {
@@ -194,28 +661,36 @@ fn get_card_suffix(last_card: &mut Option<(Rarity, i32)>, current_rarity: &Rarit
     }
 }
 
-fn configure_card(card_input: &mut CardInput, config: &Config, last_card: &mut Option<(Rarity, i32)>) -> Card {
-    let card_name = get_string(format!("Enter name for {:?} card {}: ", &card_input.rarity, get_card_suffix(last_card, &card_input.rarity)));
-    let efficiency = get_efficiency();
+fn configure_card(card_input: &mut CardInput, config: &Config, last_card: &mut Option<(Rarity, i32)>, names: &mut NameGenerator) -> Card {
+    let number = get_card_suffix(last_card, &card_input.rarity);
+    let card_name = get_string(format!("{} ", tr("prompt-deck-card-name", &[("rarity", &card_input.rarity.label()), ("number", &number)])));
+    if !card_name.is_empty() {
+        names.take(&card_name);
+    }
+    let efficiency = get_efficiency(config);
     loop {
         let mut card = Card::new(card_name.clone(), card_input.rarity.clone(), efficiency.clone(), config.clone());
         card.print_budget_mut();
         card.with_priority_allocation(get_priority_allocation(&card));
         card.print_budget_mut();
-        card.with_range(get_range());
+        card.with_range(get_range(config));
         card.print_budget_mut();
-        card.with_effect(get_effect(&card));
-         let card_result = card.build();
-        if card_result.is_ok() {
+        card.with_effects(get_effects(&card));
+        if card_name.is_empty() {
+            card.name = names.name(&card.rarity, card.range.as_ref(), &card.effects, &mut rand::thread_rng());
+        }
+        card.with_flavour(get_flavour());
+        let card_result = card.build();
+        if let Ok(built) = card_result {
             let last = if last_card.is_some() {
                 last_card.as_ref().unwrap().1 + 1
             } else {
                 1
             };
             *last_card = Some((card.rarity, last));
-            return card_result.unwrap();
+            return built;
         } else {
-            println!("Invalid configuration!");
+            println!("{}", tr("prompt-invalid-configuration", &[]));
         }
     }
 }
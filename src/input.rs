@@ -1,6 +1,6 @@
 use std::{io::Write, str::FromStr};
 
-use crate::card::*;
+use crate::{card::*, catalog::*, locale::*};
 
 pub fn get_num<T>(min: T, max: T, prompt: String) -> T
 where
@@ -14,123 +14,122 @@ where
         let mut buf = String::new();
         std::io::stdin().read_line(&mut buf).expect("Could not read buffer");
         let Ok(val) = buf.trim().parse() else {
-            println!("Could not parse {}!", buf);
+            println!("{}", tr("prompt-could-not-parse", &[("input", &buf)]));
             std::io::stdout().flush().unwrap();
             continue;
         };
         if in_range(val, min, max) {
             return val;
         }
-        println!("Not in range!");
+        println!("{}", tr("prompt-not-in-range", &[]));
         std::io::stdout().flush().unwrap();
     }
 }
 
 pub fn get_priority_allocation(card: &Card) -> i32 {
-    get_num(1, card.budget - 1, format!("Enter priority allocation (even) ({}..{}): ", 1, card.budget - 1))
+    get_num(1, card.budget - 1, format!("{} ", tr("prompt-priority-allocation", &[("min", &1), ("max", &(card.budget - 1))])))
 }
 
 pub fn display_effect_cost(effect_data: (Option<Effect>, i32)) -> String {
     if effect_data.0.is_some() {
-        String::from(format!("{}", effect_data.1))
+        format!("{}", effect_data.1)
     } else {
-        String::from("N/A")
+        tr("menu-unaffordable", &[])
     }
 }
 
-pub fn get_effect(card: &Card) -> Effect {
-    let budget = card.budget;
-    let effect_type: i32 = get_num(
+pub fn get_effects(card: &Card) -> Vec<Effect> {
+    let count = get_num(
         1,
-        4,
-        String::from(
-            format!("{}{}{}{}\nEnter effect type: (1..4).. ", 
-                pad_right(format!("1: Damage (Cost: {})", display_effect_cost(cost_from_effect(Effect::Damage(0), budget, &card.range, &card.config))), PADDING, ' '),
-                pad_right(format!("2: Heal (Cost: {})", display_effect_cost(cost_from_effect(Effect::Heal(0), budget, &card.range, &card.config))), PADDING, ' '),
-                pad_right(format!("3: Acid Healing (Cost: {})", display_effect_cost(cost_from_effect(Effect::AcidHeal(0), budget, &card.range, &card.config))), PADDING, ' '),
-                pad_right(format!("4: Shield (Cost: {})", display_effect_cost(cost_from_effect(Effect::Shield(0), budget, &card.range, &card.config))), PADDING, ' '),
-            )
-        ),
-    ) - 1;
-    match effect_type {
-        0 => Effect::Damage(0),
-        1 => Effect::Heal(0),
-        2 => Effect::AcidHeal(0),
-        3 => Effect::Shield(0),
-        _ => Effect::Damage(0),
-    }
+        MAX_EFFECTS,
+        format!("{} ", tr("prompt-effect-count", &[("max", &MAX_EFFECTS), ("surcharge", &card.config.extra_effect_surcharge)])),
+    );
+    let shares = split_budget(card.budget - effect_surcharge(count as usize, &card.config), count as usize);
+    shares.into_iter().map(|budget| get_effect(card, budget)).collect()
 }
 
-pub fn get_range() -> Range {
-    match get_num(
-        1, 
-        4,
-        String::from(
-            format!("{}{}{}{}\nEnter range type: (1..4).. ",
-                pad_right(format!("1: Single (Cost: {})", cost_from_range(&Range::Single)), PADDING, ' '),
-                pad_right(format!("2: Multiple (2) (Cost: {})", cost_from_range(&Range::Multiple)), PADDING, ' '),
-                pad_right(format!("3: AoE (room) (Cost: {})", cost_from_range(&Range::AoE)), PADDING, ' '),
-                pad_right(format!("4: AoE (Extended) (Cost: {})", cost_from_range(&Range::ExtendedAoE)), PADDING, ' '),
-            )
-        )) - 1i32 {
-        0 => Range::Single,
-        1 => Range::Multiple,
-        2 => Range::AoE,
-        3 => Range::ExtendedAoE,
-        _ => Range::Single
+pub fn get_effect(card: &Card, budget: i32) -> Effect {
+    let catalog = &card.config.effect_catalog;
+    let options: Vec<String> = catalog
+        .iter()
+        .enumerate()
+        .map(|(index, definition)| {
+            let cost = display_effect_cost(cost_from_effect(definition.effect(), budget, &card.range, &card.config));
+            let per_turn = if definition.mechanic.kind() == EffectKind::Control { tr("menu-per-turn", &[]) } else { String::new() };
+            pad_right(tr("menu-effect", &[("index", &(index + 1)), ("name", &definition.label()), ("cost", &cost), ("per_turn", &per_turn)]), PADDING, ' ')
+        })
+        .collect();
+    let menu: Vec<String> = options.chunks(4).map(|row| row.concat()).collect();
+    let effect_type = get_num(1, catalog.len() as i32, format!("{}\n{} ", menu.join("\n"), tr("prompt-effect", &[("max", &catalog.len())]))) - 1;
+    let definition = &catalog[effect_type as usize];
+    let effect = match definition.mechanic.kind() {
+        EffectKind::Instant => definition.effect(),
+        EffectKind::OverTime => get_timing(definition.effect()),
+        EffectKind::Control => get_control_duration(definition.effect()),
+    };
+    match definition.mechanic {
+        Mechanic::Search => effect.with_rarity(get_rarity()),
+        _ => effect,
     }
 }
 
-pub fn get_efficiency() -> Efficiency {
-    match get_num(
-        1,
-        3,
-        String::from(
-            format!("{}{}{}\nEnter efficiency: (1..3).. ",
-                pad_right("1: Bad".into(), PADDING, ' '),
-                pad_right("2: Normal".into(), PADDING, ' '),
-                pad_right("3: Good".into(), PADDING, ' '),
-            )),
-    ) - 1i32
-    {
-        0 => Efficiency::Bad,
-        1 => Efficiency::Normal,
-        2 => Efficiency::Good,
-        _ => Efficiency::Bad,
-    }
+pub fn get_timing(effect: Effect) -> Effect {
+    let duration = get_num(1, MAX_DURATION, format!("{} ", tr("prompt-duration", &[("max", &MAX_DURATION)])));
+    let tick = get_num(1, duration, format!("{} ", tr("prompt-tick", &[("max", &duration)])));
+    effect.with_timing(duration, tick)
+}
+
+pub fn get_control_duration(effect: Effect) -> Effect {
+    let duration = get_num(1, MAX_DURATION, format!("{} ", tr("prompt-duration", &[("max", &MAX_DURATION)])));
+    effect.with_duration(duration)
+}
+
+pub fn get_range(config: &Config) -> Range {
+    let catalog = &config.range_catalog;
+    let options: Vec<String> = catalog
+        .iter()
+        .enumerate()
+        .map(|(index, definition)| {
+            let name = config.describe_range(&definition.range());
+            pad_right(tr("menu-range", &[("index", &(index + 1)), ("name", &name), ("targets", &targets_label(&definition.targets)), ("cost", &definition.cost)]), PADDING, ' ')
+        })
+        .collect();
+    let menu: Vec<String> = options.chunks(4).map(|row| row.concat()).collect();
+    let range_type = get_num(1, catalog.len() as i32, format!("{}\n{} ", menu.join("\n"), tr("prompt-range", &[("max", &catalog.len())]))) - 1;
+    catalog[range_type as usize].range()
+}
+
+pub fn get_efficiency(config: &Config) -> Efficiency {
+    let tiers = &config.efficiency;
+    let options: Vec<String> = tiers
+        .iter()
+        .enumerate()
+        .map(|(index, tier)| pad_right(tr("menu-efficiency", &[("index", &(index + 1)), ("name", &tier.id), ("multiplier", &tier.multiplier)]), PADDING, ' '))
+        .collect();
+    let menu: Vec<String> = options.chunks(4).map(|row| row.concat()).collect();
+    let tier = get_num(1, tiers.len() as i32, format!("{}\n{} ", menu.join("\n"), tr("prompt-efficiency", &[("max", &tiers.len())]))) - 1;
+    tiers[tier as usize].efficiency()
 }
 
 pub fn get_rarity() -> Rarity {
-    match get_num(
-        1,
-        5,
-        String::from(
-            format!("{}{}{}{}{}\nEnter rarity: (1..5).. ",
-                pad_right("1: Common".into(), PADDING, ' '),
-                pad_right("2: Uncommon".into(), PADDING, ' '),
-                pad_right("3: Rare".into(), PADDING, ' '),
-                pad_right("4: Epic".into(), PADDING, ' '),
-                pad_right("5: Legendary".into(), PADDING, ' '),
-            )),
-    ) - 1i32
-    {
-        0 => Rarity::Common,
-        1 => Rarity::Uncommon,
-        2 => Rarity::Rare,
-        3 => Rarity::Epic,
-        4 => Rarity::Legendary,
-        _ => Rarity::Common,
-    }
+    let rarities = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+    let menu: String = rarities.iter().enumerate().map(|(index, rarity)| pad_right(format!("{}: {}", index + 1, rarity.label()), PADDING, ' ')).collect();
+    let rarity = get_num(1, 5, format!("{}\n{} ", menu, tr("prompt-rarity", &[]))) - 1;
+    rarities[rarity as usize].clone()
 }
 
 pub fn get_name() -> String {
     let mut buf = String::new();
-    print!("Enter card name (<Enter> to exit): ");
+    print!("{} ", tr("prompt-card-name", &[]));
     let _ = std::io::stdout().flush();
     let _ = std::io::stdin().read_line(&mut buf);
     String::from(buf.trim())
 }
 
+pub fn get_flavour() -> Option<String> {
+    Some(get_string(format!("{} ", tr("prompt-flavour", &[])))).filter(|flavour| !flavour.is_empty())
+}
+
 pub fn get_string(prompt: String) -> String {
     let mut buf = String::new();
     print!("{}", prompt);
@@ -140,10 +139,31 @@ pub fn get_string(prompt: String) -> String {
 }
 
 pub fn show_help() {
-    println!("Options:");
-    println!("--deck-template Generate a deck template \nWarning: must be placed in folder of the same name as the deck and the file renamed to <deck_name>.card all in the decks/ folder)");
-    println!("--deck-from-template Generates a deck from a template");
-    println!("--deck-generator: Interactive deck generation");
-    println!("--deck-examples: Generates example decks for all tiers");
-    println!("--generate-cards: Generate induvidual cards (written to cards/ folder)");
+    let options = [
+        "help-deck-template",
+        "help-deck-from-template",
+        "help-deck-generator",
+        "help-deck-random",
+        "help-deck-examples",
+        "help-generate-cards",
+        "help-config-check",
+        "help-deck-check",
+        "help-card-explain",
+        "help-rounding-report",
+        "help-priority-explain",
+        "help-autofill",
+        "help-retier",
+        "help-convert",
+        "help-migrate",
+        "help-schema",
+        "help-simulate",
+        "help-tune",
+        "help-locale",
+        "help-locale-missing",
+    ];
+    println!("{}", tr("help-options", &[]));
+    for option in options {
+        let path = if option == "help-tune" { crate::TUNED_CONFIG_PATH } else { crate::PATH };
+        println!("{}", tr(option, &[("path", &path)]));
+    }
 }
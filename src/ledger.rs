@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+// Step by step record of how a card's budget was spent, kept when a card is built with `with_ledger`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Ledger {
+    pub entries: Vec<LedgerEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerEntry {
+    pub step: String,
+    pub detail: String,
+    // Budget left after this step
+    pub budget: i32,
+}
+
+impl Ledger {
+    pub fn record(&mut self, step: &str, detail: String, budget: i32) {
+        self.entries.push(LedgerEntry { step: String::from(step), detail, budget });
+    }
+}
+
+impl std::fmt::Display for Ledger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in self.entries.iter() {
+            writeln!(f, "\t{}: {} (budget {})", entry.step, entry.detail, entry.budget)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::card::*;
+
+    #[test]
+    fn entries_print_one_per_line() {
+        let mut ledger = Ledger::default();
+        ledger.record("Power", String::from("rolled 10"), 10);
+        ledger.record("Range", String::from("AoE costs 2"), 8);
+        assert_eq!(ledger.to_string(), "\tPower: rolled 10 (budget 10)\n\tRange: AoE costs 2 (budget 8)\n");
+    }
+
+    #[test]
+    fn ledger_totals_match_the_built_card() {
+        let config = Config::default();
+        for input in DeckInputs::new(DeckType::Legendary).inputs.iter() {
+            let (ledger, explained) = input.explain(&config, &mut StdRng::seed_from_u64(4));
+            let (explained, built) = (explained.unwrap(), input.build(&config, &mut StdRng::seed_from_u64(4)).unwrap());
+            assert_eq!((explained.priority, explained.barnacles, explained.budget), (built.priority, built.barnacles, built.budget));
+            let detail = |step: &str| ledger.entries.iter().rev().find(|entry| entry.step == step).map(|entry| entry.detail.clone()).unwrap();
+            assert!(detail("Barnacles").ends_with(&format!("= {}", built.barnacles)));
+            assert!(detail("Withdraw").ends_with(&format!("x 0.33 = {}", built.get_withdraw())));
+            assert!(detail("Priority").ends_with(&format!("= {}", built.priority)));
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, catalog::*, locale::*, rounding::*};
+
+// What happens to budget the card's effects could not spend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum LeftoverPolicy {
+    #[default]
+    #[serde(alias = "discard")]
+    Discard,
+    // Buys extra magnitude for the first effect that can afford more
+    #[serde(alias = "magnitude")]
+    Magnitude,
+    // Takes barnacles off the cast cost as if the leftover were effect magnitude
+    #[serde(alias = "barnacles")]
+    Barnacles,
+    // Adds the leftover to the priority allocation
+    #[serde(alias = "priority")]
+    Priority,
+    // Fails the build so the card can be rebalanced
+    #[serde(alias = "reject")]
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Leftover {
+    Discarded { amount: Fraction },
+    Magnitude { amount: Fraction, effect: String, magnitude: i32 },
+    Barnacles { amount: Fraction, barnacles: i32 },
+    Priority { amount: i32 },
+}
+
+impl Leftover {
+    pub fn amount(&self) -> Fraction {
+        match self {
+            Leftover::Discarded { amount } | Leftover::Magnitude { amount, .. } | Leftover::Barnacles { amount, .. } => *amount,
+            Leftover::Priority { amount } => Fraction::from(*amount),
+        }
+    }
+}
+
+impl std::fmt::Display for Leftover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Leftover::Discarded { amount } => write!(f, "{}", tr("leftover-discarded", &[("amount", amount)])),
+            Leftover::Magnitude { amount, effect, magnitude } => write!(f, "{}", tr("leftover-magnitude", &[("amount", amount), ("magnitude", magnitude), ("effect", effect)])),
+            Leftover::Barnacles { amount, barnacles } => write!(f, "{}", tr("leftover-barnacles", &[("amount", amount), ("barnacles", barnacles)])),
+            Leftover::Priority { amount } => write!(f, "{}", tr("leftover-priority", &[("amount", amount)])),
+        }
+    }
+}
+
+// The effect with its magnitude raised by what `leftover` can buy, and what the extra magnitude costs
+pub fn grow_effect(effect: &Effect, leftover: Fraction, range: &Option<Range>, config: &Config) -> Option<(Effect, Fraction)> {
+    if config.mechanic(effect)?.kind() == EffectKind::Control {
+        return None;
+    }
+    let modifier = config.effect_modifier(effect, range.as_ref()?)?;
+    let current = config.rounding.scale(Fraction::from(effect.magnitude), modifier);
+    let (grown, used) = price_effect(effect.clone(), current + leftover, range, config);
+    let grown = grown.filter(|grown| grown.magnitude > effect.magnitude)?;
+    Some((grown, used - current))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn build(input: &CardInput, policy: LeftoverPolicy) -> Result<Card, String> {
+        let config = Config { leftover: policy, ..Config::default() };
+        input.build(&config, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn leftover_budget_follows_the_policy() {
+        let mut input = CardInput::new(Rarity::Legendary);
+        input.range = Range::new("AoE");
+        input.effects = vec![Effect::new("Heal"), Effect::new("Heal")];
+        let discarded = build(&input, LeftoverPolicy::Discard).unwrap();
+        let Some(Leftover::Discarded { amount }) = discarded.leftover else {
+            panic!("expected leftover budget, found {:?}", discarded.leftover);
+        };
+        assert!(amount > Fraction::zero());
+
+        let grown = build(&input, LeftoverPolicy::Magnitude).unwrap();
+        assert!(matches!(grown.leftover, Some(Leftover::Magnitude { magnitude: 1, .. })));
+        assert_eq!(grown.effects[0].magnitude, discarded.effects[0].magnitude + 1);
+        assert!(build(&input, LeftoverPolicy::Barnacles).unwrap().barnacles < discarded.barnacles);
+        let banked = build(&input, LeftoverPolicy::Priority).unwrap();
+        assert_eq!(banked.leftover, Some(Leftover::Priority { amount: amount.floor() }));
+        assert!(build(&input, LeftoverPolicy::Reject).unwrap_err().contains("leftover policy rejects"));
+    }
+
+    #[test]
+    fn reject_counts_the_fraction_exact_rounding_leaves() {
+        let mut input = CardInput::new(Rarity::Rare);
+        input.effects = vec![Effect::new("Heal")];
+        let config = |leftover: LeftoverPolicy| Config { leftover, rounding: RoundingPolicy::Exact, ..Config::default() };
+        let discarded = input.build(&config(LeftoverPolicy::Discard), &mut StdRng::seed_from_u64(0)).unwrap();
+        let Some(Leftover::Discarded { amount }) = discarded.leftover else {
+            panic!("expected leftover budget, found {:?}", discarded.leftover);
+        };
+        assert!(amount > Fraction::zero() && amount.floor() == 0, "{}", amount);
+        let err = input.build(&config(LeftoverPolicy::Reject), &mut StdRng::seed_from_u64(0)).unwrap_err();
+        assert_eq!(err, format!("{} budget left over, the leftover policy rejects cards that do not spend their whole budget", amount));
+    }
+}
@@ -0,0 +1,30 @@
+use std::{fs::OpenOptions, io::Write};
+use crate::{input::*, card::*};
+
+pub mod autofill;
+pub mod card;
+pub mod catalog;
+pub mod conditions;
+pub mod formats;
+pub mod input;
+pub mod ledger;
+pub mod leftover;
+pub mod locale;
+pub mod migrations;
+pub mod names;
+pub mod priority;
+pub mod retier;
+pub mod rounding;
+pub mod generators;
+pub mod schema;
+pub mod simulator;
+pub mod strategy;
+pub mod templates;
+pub mod themes;
+pub mod tuning;
+pub mod validation;
+
+pub static PATH: &str = "config.json";
+pub static ALTERNATE_CONFIG_PATHS: [&str; 4] = ["config.toml", "config.yaml", "config.yml", "config.ron"];
+pub static TUNED_CONFIG_PATH: &str = "config.tuned.json";
+pub static TUNING_REPORT_PATH: &str = "config.tuned.report.txt";
@@ -0,0 +1,225 @@
+use std::{collections::BTreeMap, fmt::Display, sync::OnceLock};
+
+use crate::{card::*, catalog::*, rounding::*, templates::*};
+
+pub static LOCALES_FOLDER: &str = "locales/";
+pub static DEFAULT_LOCALE: &str = "en";
+
+// Messages for one locale, read from a file of `key = text` lines
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    pub locale: String,
+    pub messages: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    // Blank lines and lines starting with # are skipped, \n in a message starts a new line. Only this subset of
+    // Fluent is read, multiline messages, attributes, terms and selectors are rejected rather than misread
+    pub fn parse(locale: &str, source: &str) -> Result<Catalog, String> {
+        let mut messages = BTreeMap::new();
+        for (number, line) in source.lines().enumerate() {
+            if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                return Err(format!("{}.ftl line {}: multiline messages are not supported, keep each message on its own `key = text` line and use \\n for new lines", locale, number + 1));
+            }
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, text)) = line.split_once('=') else {
+                return Err(format!("{}.ftl line {}: expected `key = text`", locale, number + 1));
+            };
+            let key = key.trim();
+            if key.starts_with('-') || key.starts_with('.') {
+                return Err(format!("{}.ftl line {}: Fluent terms and attributes are not supported, {} must be a plain key", locale, number + 1, key));
+            }
+            if text.contains("->") {
+                return Err(format!("{}.ftl line {}: Fluent selectors are not supported, only {{ $name }} placeholders are", locale, number + 1));
+            }
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return Err(format!("{}.ftl line {}: bad key {:?}", locale, number + 1, key));
+            }
+            if messages.insert(String::from(key), text.trim().replace("\\n", "\n")).is_some() {
+                return Err(format!("{}.ftl line {}: {} is defined twice", locale, number + 1, key));
+            }
+        }
+        Ok(Catalog { locale: String::from(locale), messages })
+    }
+
+    pub fn english() -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        ENGLISH.get_or_init(|| Catalog::parse(DEFAULT_LOCALE, include_str!("../locales/en.ftl")).expect("Built-in English messages do not parse"))
+    }
+
+    pub fn load(locale: &str) -> Result<Catalog, String> {
+        let path = format!("{}{}.ftl", LOCALES_FOLDER, locale);
+        match std::fs::read_to_string(&path) {
+            Ok(source) => Catalog::parse(locale, &source),
+            Err(_) if locale == DEFAULT_LOCALE => Ok(Catalog::english().clone()),
+            Err(_) => Err(format!("Could not read {}", path)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+static ACTIVE: OnceLock<Catalog> = OnceLock::new();
+
+// Only the first locale set is used, later calls are ignored
+pub fn set_locale(catalog: Catalog) {
+    let _ = ACTIVE.set(catalog);
+}
+
+fn message(key: &str) -> Option<&'static str> {
+    ACTIVE.get().and_then(|catalog| catalog.get(key)).or_else(|| Catalog::english().get(key))
+}
+
+// Replaces every { $name } with its value, braces without a $ are left alone
+fn fill(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            filled.push_str(&rest[start..]);
+            return filled;
+        };
+        let placeholder = &rest[start..start + end + 1];
+        let value = placeholder[1..placeholder.len() - 1].trim().strip_prefix('$').and_then(|name| args.iter().find(|(arg, _)| *arg == name.trim()));
+        match value {
+            Some((_, value)) => filled.push_str(&value.to_string()),
+            None => filled.push_str(placeholder),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+// The message in the active locale, then in English, then the key itself
+pub fn tr(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    fill(message(key).unwrap_or(key), args)
+}
+
+// For keys made from config ids, which English has no messages for
+pub fn tr_or(key: &str, fallback: &str, args: &[(&str, &dyn Display)]) -> String {
+    fill(message(key).unwrap_or(fallback), args)
+}
+
+fn normalised_key(prefix: &str, id: &str) -> String {
+    format!("{}-{}", prefix, normalise_id(id))
+}
+
+pub fn effect_name_key(id: &str) -> String {
+    normalised_key("effect-name", id)
+}
+
+pub fn range_name_key(id: &str) -> String {
+    normalised_key("range-name", id)
+}
+
+pub fn rules_key(template: &RulesTemplate) -> String {
+    match &template.range_id {
+        Some(range) => format!("{}-{}", normalised_key("rules-text", &template.effect_id), normalise_id(range)),
+        None => normalised_key("rules-text", &template.effect_id),
+    }
+}
+
+impl Rarity {
+    pub fn label(&self) -> String {
+        tr(&format!("rarity-{}", format!("{:?}", self).to_lowercase()), &[])
+    }
+}
+
+impl DeckType {
+    pub fn label(&self) -> String {
+        tr(&format!("deck-type-{}", format!("{:?}", self).to_lowercase()), &[])
+    }
+}
+
+impl RoundingPolicy {
+    pub fn label(&self) -> String {
+        match self {
+            RoundingPolicy::Floor => tr("rounding-policy-floor", &[]),
+            RoundingPolicy::HalfEven => tr("rounding-policy-half-even", &[]),
+            RoundingPolicy::Ceil => tr("rounding-policy-ceil", &[]),
+            RoundingPolicy::Exact => tr("rounding-policy-exact", &[]),
+        }
+    }
+}
+
+pub fn targets_label(targets: &Targets) -> String {
+    match targets {
+        Targets::Count { count } => tr("targets-count", &[("count", count)]),
+        Targets::Random { count } => tr("targets-random", &[("count", count)]),
+        Targets::Room => tr("targets-room", &[]),
+        Targets::Party => tr("targets-party", &[]),
+        Targets::Allies => tr("targets-allies", &[]),
+        Targets::Caster => tr("targets-caster", &[]),
+        Targets::Line { count } => tr("targets-line", &[("count", count)]),
+        Targets::Cone { spread } => tr("targets-cone", &[("spread", spread)]),
+    }
+}
+
+// Every key a locale can translate with its English text: the built-in messages, then the names and rules text of the config
+pub fn translatable(config: &Config) -> Vec<(String, String)> {
+    let mut keys: Vec<(String, String)> = Catalog::english().messages.iter().map(|(key, text)| (key.clone(), text.clone())).collect();
+    keys.extend(config.effect_catalog.iter().map(|definition| (effect_name_key(&definition.id), definition.name.clone())));
+    keys.extend(config.range_catalog.iter().map(|definition| (range_name_key(&definition.id), definition.name.clone())));
+    keys.extend(config.rules_templates.iter().map(|template| (rules_key(template), template.text.clone())));
+    keys
+}
+
+// Keys the catalog leaves out, and keys it has that nothing reads, English already has the config's own text
+pub fn missing_keys(catalog: &Catalog, config: &Config) -> (Vec<(String, String)>, Vec<String>) {
+    let keys = translatable(config);
+    let missing = keys
+        .iter()
+        .filter(|(key, _)| catalog.get(key).is_none() && (catalog.locale != DEFAULT_LOCALE || Catalog::english().get(key).is_some()))
+        .cloned()
+        .collect();
+    let unknown = catalog.messages.keys().filter(|key| !keys.iter().any(|(known, _)| known == *key)).cloned().collect();
+    (missing, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_by_name() {
+        assert_eq!(fill("{ $name } ({ $magnitude })", &[("magnitude", &3), ("name", &"Heal")]), "Heal (3)");
+        assert_eq!(fill("{ $missing } and {braces}", &[]), "{ $missing } and {braces}");
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_english_then_the_key() {
+        assert_eq!(tr("rarity-rare", &[]), "Rare");
+        assert_eq!(tr("no-such-key", &[]), "no-such-key");
+        assert_eq!(tr_or(&effect_name_key("Damage"), "Damage", &[]), "Damage");
+    }
+
+    #[test]
+    fn only_single_line_messages_parse() {
+        let catalog = Catalog::parse("fr", "# Commentaire\n\nrarity-rare = Rare\ncard-rules = Règles : { $rules }\\nfin").unwrap();
+        assert_eq!(catalog.get("card-rules"), Some("Règles : { $rules }\nfin"));
+        assert!(Catalog::parse("fr", "rarity-rare = Rare\nrarity-rare = Rare").unwrap_err().contains("defined twice"));
+        assert!(Catalog::parse("fr", "card-rules =\n    Règles").unwrap_err().contains("multiline"));
+        assert!(Catalog::parse("fr", "-brand = Barnacles").unwrap_err().contains("terms"));
+        assert!(Catalog::parse("fr", "effect-control-turns = { $duration ->").unwrap_err().contains("selectors"));
+    }
+
+    #[test]
+    fn missing_keys_include_the_config_text() {
+        let config = Config::default();
+        let (missing, unknown) = missing_keys(Catalog::english(), &config);
+        assert!(missing.is_empty() && unknown.is_empty());
+        let partial = Catalog::parse("fr", "rarity-rare = Rare\nrarity-mythic = Mythique").unwrap();
+        let (missing, unknown) = missing_keys(&partial, &config);
+        assert_eq!(unknown, ["rarity-mythic"]);
+        assert!(!missing.iter().any(|(key, _)| key == "rarity-rare"));
+        assert!(missing.contains(&(String::from("effect-name-acidheal"), String::from("Acid Heal"))));
+        assert_eq!(missing.len(), translatable(&config).len() - 1);
+    }
+}
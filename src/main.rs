@@ -1,24 +1,47 @@
-use std::{fs::OpenOptions, io::{Read, Write}};
-use crate::{input::*, card::*, generators::*};
-
-mod card;
-mod input;
-mod generators;
-
-static PATH: &str = "config.json";
+use card_generator::{card::*, formats::*, generators::*, input::*, locale::*, migrations::*, schema::*};
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--locale") {
+        let locale = args.get(index + 1).cloned().unwrap_or_default();
+        args.drain(index..(index + 2).min(args.len()));
+        match Catalog::load(&locale) {
+            Ok(catalog) => set_locale(catalog),
+            Err(err) => println!("WARNING: {}, using English", err),
+        }
+    }
+    let arg = |index: usize| args.get(index).cloned();
+    let rest = || args.iter().skip(2).cloned().collect::<Vec<String>>();
+    let command = arg(1).unwrap_or(String::from(""));
+    // These commands work on configs that are outdated or fail validation, so they skip load_config
+    match command.as_str() {
+        "--config-check" => std::process::exit(check_config(read_config())),
+        "--convert" => return convert_document(arg(2), arg(3)),
+        "--migrate" => return migrate_files(rest()),
+        "--schema" => return write_schemas(arg(2), &read_config()),
+        _ => {}
+    }
     let config = load_config();
-    match std::env::args().nth(1).unwrap_or(String::from("")).as_str() {
+    match command.as_str() {
         "--deck-template" => generate_deck_file(),
-        "--deck-from-template" => generate_deck_from_template(std::env::args().nth(2), config),
+        "--deck-from-template" => generate_deck_from_template(arg(2), config),
         "--deck-generator" => generate_deck(),
+        "--deck-random" => generate_random_deck(rest(), config),
         "--deck-examples" => {
             generate_deck_from_template(Some(String::from("starter")), config.clone());
             generate_deck_from_template(Some(String::from("journeyman")), config.clone());
             generate_deck_from_template(Some(String::from("legendary")), config.clone());
         },
         "--generate-cards" => generate_cards(config),
+        "--deck-check" => std::process::exit(check_deck(arg(2), config)),
+        "--card-explain" => explain_cards(rest(), config),
+        "--rounding-report" => rounding_report(rest(), config),
+        "--priority-explain" => explain_priority(rest(), config),
+        "--autofill" => autofill_deck(rest(), config),
+        "--retier" => retier_deck(rest(), config),
+        "--simulate" => simulate_decks(rest(), config),
+        "--tune" => tune_config(rest(), config),
+        "--locale-missing" => std::process::exit(list_missing_translations(arg(2), config)),
         _ => show_help()
     };
 }
@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{card::*, catalog::*, formats::*};
+
+pub type Migration = fn(&mut Value) -> Result<(), String>;
+
+// Documents that carry a `version` field, MIGRATIONS[n] upgrades a document from version n to n + 1
+pub trait Versioned: DeserializeOwned {
+    const KIND: &'static str;
+    const VERSION: u32;
+    const MIGRATIONS: &'static [Migration];
+}
+
+impl Versioned for DeckInputs {
+    const KIND: &'static str = "deck";
+    const VERSION: u32 = 2;
+    const MIGRATIONS: &'static [Migration] = &[unversioned_to_v1, single_effect_to_effects];
+}
+
+impl Versioned for Config {
+    const KIND: &'static str = "config";
+    const VERSION: u32 = 2;
+    const MIGRATIONS: &'static [Migration] = &[unversioned_to_v1, effect_tables_to_catalog];
+}
+
+// Documents written before versioning only lack the version field itself
+fn unversioned_to_v1(_: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+// Cards used to hold a single `effect`, they now hold an ordered list of `effects`
+fn single_effect_to_effects(value: &mut Value) -> Result<(), String> {
+    let Some(Value::Array(inputs)) = value.get_mut("inputs") else {
+        return Err(String::from("Deck has no inputs"));
+    };
+    for input in inputs.iter_mut().filter_map(|input| input.as_object_mut()) {
+        if let Some(effect) = input.remove("effect") {
+            input.insert(String::from("effects"), Value::Array(vec![effect]));
+        }
+    }
+    Ok(())
+}
+
+// Effects used to be priced from one range table per effect, they now live in a single `effect_catalog`
+fn effect_tables_to_catalog(value: &mut Value) -> Result<(), String> {
+    let Value::Object(map) = value else {
+        return Err(String::from("Config is not an object"));
+    };
+    let mut catalog = builtin_catalog();
+    let range_tables = [
+        ("damage_range_modifiers", "Damage"),
+        ("heal_range_modifiers", "Heal"),
+        ("acid_heal_range_modifiers", "AcidHeal"),
+        ("shield_heal_range_modifiers", "Shield"),
+    ];
+    for (key, id) in range_tables {
+        let Some(table) = map.remove(key) else {
+            continue;
+        };
+        // The tables had one fixed key per range, the catalog keys them by range id
+        let modifier = |range: &str| table.get(range).and_then(Value::as_f64).map(|modifier| modifier as f32).ok_or_else(|| format!("{}.{}: expected a number", key, range));
+        let modifiers = range_modifiers(modifier("single")?, modifier("multiple")?, modifier("aoe")?, modifier("aoe_extended")?);
+        if let Some(definition) = catalog.iter_mut().find(|definition| definition.matches(id)) {
+            definition.range_modifiers = modifiers;
+        }
+    }
+    map.insert(String::from("effect_catalog"), serde_json::to_value(&catalog).map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+pub fn document_version(value: &Value) -> u32 {
+    value.get("version").and_then(|version| version.as_u64()).unwrap_or(0) as u32
+}
+
+// Upgrades the document in place to the current version, returning the version it started at
+pub fn migrate<T: Versioned>(value: &mut Value) -> Result<u32, String> {
+    let version = document_version(value);
+    if version > T::VERSION {
+        return Err(format!("{} version {} is newer than the latest supported version ({})", T::KIND, version, T::VERSION));
+    }
+    if T::MIGRATIONS.len() != T::VERSION as usize {
+        return Err(format!("Missing {} migrations, expected {} but found {}", T::KIND, T::VERSION, T::MIGRATIONS.len()));
+    }
+    for (from, migration) in T::MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(value).map_err(|err| format!("Could not migrate {} from version {}: {}", T::KIND, from, err))?;
+    }
+    if let Value::Object(map) = value {
+        map.insert(String::from("version"), Value::from(T::VERSION));
+    }
+    Ok(version)
+}
+
+fn migrate_document<T: Versioned + serde::Serialize>(path: &Path, mut value: Value) -> Result<Option<u32>, String> {
+    let version = migrate::<T>(&mut value)?;
+    if version == T::VERSION {
+        return Ok(None);
+    }
+    let document: T = serde_json::from_value(value).map_err(|err| err.to_string())?;
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    std::fs::copy(path, &backup).map_err(|_| format!("Could not back up file to {:?}", backup))?;
+    write_document(path, &document)?;
+    Ok(Some(version))
+}
+
+fn migratable_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::iter::once(crate::PATH)
+        .chain(crate::ALTERNATE_CONFIG_PATHS)
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .collect();
+    let Ok(decks) = std::fs::read_dir("decks") else {
+        return files;
+    };
+    for deck in decks.filter_map(|res| res.ok()).map(|dir| dir.path()) {
+        let Some(name) = deck.file_name().and_then(|name| name.to_str()).map(String::from) else {
+            continue;
+        };
+        files.extend(
+            DECK_EXTENSIONS
+                .iter()
+                .map(|extension| deck.join(format!("{}.{}", name, extension)))
+                .filter(|path| path.exists()),
+        );
+    }
+    files
+}
+
+// Rewrites outdated decks and configs in place, keeping the original next to it as <file>.bak
+pub fn migrate_files(paths: Vec<String>) {
+    let paths = if paths.is_empty() { migratable_files() } else { paths.into_iter().map(PathBuf::from).collect() };
+    for path in paths.iter() {
+        let result = read_value(path).and_then(|value| {
+            if value.get("inputs").is_some() {
+                migrate_document::<DeckInputs>(path, value)
+            } else {
+                migrate_document::<Config>(path, value)
+            }
+        });
+        match result {
+            Ok(Some(version)) => println!("Migrated {} from version {}", path.display(), version),
+            Ok(None) => println!("{} is up to date", path.display()),
+            Err(err) => println!("Could not migrate {}: {}", path.display(), err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_decks_move_to_an_effect_list() {
+        let mut value = serde_json::json!({ "inputs": [{ "name": "Zap", "effect": { "type": "Damage", "magnitude": 0 } }] });
+        assert_eq!(migrate::<DeckInputs>(&mut value), Ok(0));
+        assert_eq!(value, serde_json::json!({ "inputs": [{ "name": "Zap", "effects": [{ "type": "Damage", "magnitude": 0 }] }], "version": 2 }));
+        assert_eq!(migrate::<DeckInputs>(&mut value), Ok(2));
+    }
+
+    #[test]
+    fn newer_documents_are_rejected() {
+        let mut value = serde_json::json!({ "version": 99, "inputs": [] });
+        assert_eq!(migrate::<DeckInputs>(&mut value), Err(String::from("deck version 99 is newer than the latest supported version (2)")));
+        assert_eq!(value["version"], 99);
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(<DeckInputs as Versioned>::MIGRATIONS.len(), <DeckInputs as Versioned>::VERSION as usize);
+        assert_eq!(<Config as Versioned>::MIGRATIONS.len(), <Config as Versioned>::VERSION as usize);
+    }
+
+    #[test]
+    fn effect_tables_become_the_catalog() {
+        let mut value = serde_json::json!({
+            "damage_range_modifiers": { "single": 2.0, "multiple": 3.0, "aoe": 4.0, "aoe_extended": 5.0 },
+            "heal_range_modifiers": { "single": 1.0, "multiple": 1.0, "aoe": 1.0, "aoe_extended": 1.0 },
+        });
+        assert_eq!(effect_tables_to_catalog(&mut value), Ok(()));
+        let catalog = value["effect_catalog"].as_array().unwrap();
+        assert_eq!(catalog.len(), builtin_catalog().len());
+        assert_eq!(catalog[0], serde_json::json!({ "id": "Damage", "name": "Damage", "mechanic": "Damage",
+            "range_modifiers": { "Single": 2.0, "Multiple": 3.0, "AoE": 4.0, "ExtendedAoE": 5.0 }, "barnacle_multiplier": 1.0 }));
+        assert_eq!(catalog[1]["range_modifiers"]["AoE"], 1.0);
+        assert!(value.get("damage_range_modifiers").is_none());
+    }
+
+    #[test]
+    fn committed_decks_are_at_the_latest_version() {
+        for deck in std::fs::read_dir("decks").unwrap().filter_map(|entry| entry.ok()) {
+            let name = deck.file_name().into_string().unwrap();
+            let path = deck.path().join(format!("{}.deck", name));
+            let value = read_value(&path).unwrap();
+            assert_eq!(document_version(&value), <DeckInputs as Versioned>::VERSION, "{}", name);
+            assert!(value["inputs"].as_array().unwrap().iter().all(|input| input.get("effects").is_some()), "{}", name);
+            assert!(std::fs::read_to_string(&path).unwrap().ends_with('\n'), "{}", name);
+        }
+    }
+
+    #[test]
+    fn outdated_configs_migrate_even_when_invalid() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        let map = value.as_object_mut().unwrap();
+        map.remove("effect_catalog");
+        map.insert(String::from("version"), Value::from(1));
+        map.insert(String::from("damage_range_modifiers"), serde_json::json!({ "single": 2.0, "multiple": 3.0, "aoe": 4.0, "aoe_extended": 5.0 }));
+        value["power_to_priority"]["common"] = Value::from(0.0);
+        let path = std::env::temp_dir().join(format!("card-generator-{}-config.json", std::process::id()));
+        std::fs::write(&path, value.to_string()).unwrap();
+        migrate_files(vec![path.display().to_string()]);
+        let config: Config = read_document(&path).unwrap();
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        let _ = (std::fs::remove_file(&path), std::fs::remove_file(backup));
+        assert_eq!(config.version, 2);
+        assert_eq!(config.effect_catalog[0].range_modifiers.0, range_modifiers(2.0, 3.0, 4.0, 5.0).0);
+        assert!(!config.validate().is_valid());
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use rand::{seq::SliceRandom, Rng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, catalog::*, themes::*};
+
+// Words generated card names are made from, a name is an adjective from the card's range or rarity
+// followed by a noun from its first effect
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NameLists {
+    // Nouns keyed by effect id
+    #[serde(default)]
+    pub effects: BTreeMap<String, Vec<String>>,
+    // Adjectives keyed by range id
+    #[serde(default)]
+    pub ranges: BTreeMap<String, Vec<String>>,
+    // Adjectives keyed by rarity
+    #[serde(default)]
+    pub rarities: BTreeMap<String, Vec<String>>,
+}
+
+fn word_lists(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+    entries.iter().map(|(id, words)| (String::from(*id), words.iter().map(|word| String::from(*word)).collect())).collect()
+}
+
+impl Default for NameLists {
+    fn default() -> Self {
+        NameLists {
+            effects: word_lists(&[
+                ("Damage", &["Barrage", "Strike", "Lash", "Blow"]),
+                ("Heal", &["Remedy", "Salve", "Respite", "Mending"]),
+                ("AcidHeal", &["Brine", "Draught", "Tonic"]),
+                ("Shield", &["Bulwark", "Shell", "Ward", "Carapace"]),
+                ("Poison", &["Venom", "Blight", "Toxin"]),
+                ("Regeneration", &["Renewal", "Bloom", "Regrowth"]),
+                ("TimedShield", &["Barrier", "Aegis", "Screen"]),
+                ("Stun", &["Shock", "Daze", "Jolt"]),
+                ("Slow", &["Undertow", "Mire", "Drag"]),
+                ("Taunt", &["Challenge", "Bellow", "Lure"]),
+                ("Silence", &["Hush", "Muffle", "Stillness"]),
+                ("GainBarnacles", &["Haul", "Windfall", "Bounty"]),
+                ("DrainBarnacles", &["Plunder", "Leech", "Siphon"]),
+                ("Discount", &["Bargain", "Favour", "Deal"]),
+                ("Draw", &["Insight", "Foresight", "Reading"]),
+                ("Discard", &["Disarm", "Fumble", "Scatter"]),
+                ("ReturnToHand", &["Recall", "Rebound", "Ebb"]),
+                ("Search", &["Dredge", "Delve", "Scour"]),
+            ]),
+            ranges: word_lists(&[
+                ("Single", &["Piercing", "Focused", "Lone", "Pinpoint"]),
+                ("Multiple", &["Twin", "Forked", "Split", "Scattered"]),
+                ("AoE", &["Tidal", "Sweeping", "Rolling", "Crashing"]),
+                ("ExtendedAoE", &["Abyssal", "Oceanic", "Endless", "Stormborne"]),
+                ("Self", &["Shelled", "Hermit", "Inward"]),
+                ("Random", &["Wild", "Drifting", "Errant"]),
+                ("Line", &["Lancing", "Raking", "Rippling"]),
+                ("Cone", &["Fanning", "Spraying", "Spreading"]),
+                ("Allies", &["Rallying", "Shoaling", "Kindred"]),
+            ]),
+            rarities: word_lists(&[
+                ("Common", &[]),
+                ("Uncommon", &["Keen"]),
+                ("Rare", &["Gleaming", "Fabled"]),
+                ("Epic", &["Ancient", "Mythic"]),
+                ("Legendary", &["Sovereign", "Eternal"]),
+            ]),
+        }
+    }
+}
+
+fn words_for<'a>(lists: &'a BTreeMap<String, Vec<String>>, id: &str) -> &'a [String] {
+    lists.iter().find(|(key, _)| same_id(key, id)).map_or(&[], |(_, words)| words.as_slice())
+}
+
+// Names cards from the config's word lists, never handing out the same name twice
+pub struct NameGenerator<'a> {
+    config: &'a Config,
+    theme: Option<&'a Theme>,
+    taken: Vec<String>,
+}
+
+impl<'a> NameGenerator<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        NameGenerator { config, theme: None, taken: Vec::new() }
+    }
+
+    // Adds the theme's adjectives and nouns to the word lists
+    pub fn with_theme(mut self, theme: &'a Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    // Names already in use, e.g. by the other cards of a deck
+    pub fn take(&mut self, name: &str) {
+        self.taken.push(String::from(name));
+    }
+
+    pub fn name<R: Rng + ?Sized>(&mut self, rarity: &Rarity, range: Option<&Range>, effects: &[Effect], rng: &mut R) -> String {
+        let lists = &self.config.names;
+        let mut adjectives: Vec<&String> = range.map_or(&[][..], |range| words_for(&lists.ranges, &range.id)).iter().collect();
+        adjectives.extend(words_for(&lists.rarities, &format!("{:?}", rarity)));
+        let mut nouns: Vec<&String> = effects.first().map_or(&[][..], |effect| words_for(&lists.effects, &effect.id)).iter().collect();
+        if let Some(theme) = self.theme {
+            adjectives.extend(theme.adjectives.iter());
+            nouns.extend(theme.nouns.iter());
+        }
+        let fallback = effects.first().map_or(format!("{:?}", rarity), |effect| self.config.definition(effect).map_or(effect.id.clone(), |definition| definition.name.clone()));
+        let mut names: Vec<String> = match (adjectives.is_empty(), nouns.is_empty()) {
+            (_, true) => adjectives.iter().map(|adjective| format!("{} {}", adjective, fallback)).collect(),
+            (true, false) => nouns.iter().map(|noun| noun.to_string()).collect(),
+            (false, false) => adjectives.iter().flat_map(|adjective| nouns.iter().map(move |noun| format!("{} {}", adjective, noun))).collect(),
+        };
+        names.shuffle(rng);
+        let name = match names.iter().find(|name| !self.taken.contains(name)) {
+            Some(name) => name.clone(),
+            None => {
+                let base = names.first().cloned().unwrap_or(fallback);
+                (1..).map(|number| if number == 1 { base.clone() } else { format!("{} {}", base, number) }).find(|name| !self.taken.contains(name)).unwrap_or(base)
+            }
+        };
+        self.taken.push(name.clone());
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn names_are_never_handed_out_twice() {
+        let mut config = Config::default();
+        config.names.ranges.clear();
+        config.names.rarities.clear();
+        config.names.effects = word_lists(&[("Damage", &["Strike"])]);
+        let mut generator = NameGenerator::new(&config);
+        generator.take("Strike");
+        let mut rng = StdRng::seed_from_u64(0);
+        let names: Vec<String> = (0..3).map(|_| generator.name(&Rarity::Rare, None, &[Effect::new("Damage")], &mut rng)).collect();
+        assert_eq!(names, ["Strike 2", "Strike 3", "Strike 4"]);
+    }
+
+    #[test]
+    fn names_come_from_the_range_and_effect_words() {
+        let config = Config::default();
+        let mut generator = NameGenerator::new(&config);
+        let name = generator.name(&Rarity::Common, Some(&Range::new("AoE")), &[Effect::new("Heal")], &mut StdRng::seed_from_u64(0));
+        let (adjective, noun) = name.split_once(' ').unwrap();
+        assert!(words_for(&config.names.ranges, "AoE").iter().any(|word| word == adjective), "{}", name);
+        assert!(words_for(&config.names.effects, "Heal").iter().any(|word| word == noun), "{}", name);
+        assert!(config.names.effects.keys().all(|id| config.effect_catalog.iter().any(|definition| definition.matches(id))));
+    }
+}
@@ -0,0 +1,177 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, locale::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Rounding {
+    #[serde(alias = "down")]
+    Down,
+    #[serde(alias = "nearest")]
+    Nearest,
+    #[serde(alias = "up")]
+    Up,
+}
+
+impl Rounding {
+    pub fn apply(&self, value: f32) -> i32 {
+        match self {
+            Rounding::Down => value.floor() as i32,
+            Rounding::Nearest => value.round() as i32,
+            Rounding::Up => value.ceil() as i32,
+        }
+    }
+}
+
+impl std::fmt::Display for Rounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rounding::Down => write!(f, "{}", tr("rounding-down", &[])),
+            Rounding::Nearest => write!(f, "{}", tr("rounding-nearest", &[])),
+            Rounding::Up => write!(f, "{}", tr("rounding-up", &[])),
+        }
+    }
+}
+
+// Bounds on the final priority of every card of a rarity
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct RarityLimit {
+    pub rarity: Rarity,
+    #[schemars(range(min = 0))]
+    pub min: i32,
+    #[schemars(range(min = 0))]
+    pub max: i32,
+}
+
+// Cards start at max_priority and the priority bought with their allocation is taken off,
+// casts resolve lowest priority first
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct PriorityModel {
+    #[schemars(range(min = 1))]
+    pub max_priority: i32,
+    // Priority taken off is moved up to the next value `offset` above a multiple of `step`
+    #[schemars(range(min = 1))]
+    pub step: i32,
+    #[schemars(range(min = 0))]
+    pub offset: i32,
+    pub rounding: Rounding,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rarity_limits: Vec<RarityLimit>,
+}
+
+impl Default for PriorityModel {
+    fn default() -> Self {
+        PriorityModel { max_priority: 11, step: 2, offset: 1, rounding: Rounding::Down, rarity_limits: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriorityTrace {
+    pub priority: i32,
+    // Priority taken off max_priority, 0 when nothing was allocated
+    pub deduction: i32,
+    pub steps: Vec<String>,
+}
+
+impl std::fmt::Display for PriorityTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "\t{}", step)?;
+        }
+        write!(f, "\tPriority: {}", self.priority)
+    }
+}
+
+impl PriorityModel {
+    pub fn derive(&self, allocation: i32, rarity: &Rarity, modifier: f32) -> PriorityTrace {
+        let mut steps = Vec::new();
+        let deduction = if allocation < 0 {
+            steps.push(format!("Nothing allocated, priority stays at {}", self.max_priority));
+            0
+        } else {
+            let raw = allocation as f32 * modifier;
+            let rounded = self.rounding.apply(raw);
+            steps.push(format!("{} allocated x {} ({} power_to_priority) = {}, rounded {} to {}", allocation, modifier, rarity.label(), raw, self.rounding, rounded));
+            let mut value = rounded.clamp(1, self.max_priority);
+            if value != rounded {
+                steps.push(format!("Clamped to 1..{}: {}", self.max_priority, value));
+            }
+            let remainder = (value - self.offset).rem_euclid(self.step.max(1));
+            if remainder != 0 {
+                let snapped = value + self.step - remainder;
+                let snapped = if snapped > self.max_priority { snapped - self.step } else { snapped };
+                steps.push(format!("Moved from {} to {} to land {} above a multiple of {}", value, snapped, self.offset, self.step));
+                value = snapped;
+            }
+            value
+        };
+        let mut priority = self.max_priority - deduction;
+        steps.push(format!("{} - {} = {}", self.max_priority, deduction, priority));
+        if let Some(limit) = self.rarity_limits.iter().find(|limit| limit.rarity == *rarity) {
+            let limited = priority.clamp(limit.min, limit.max.max(limit.min));
+            if limited != priority {
+                steps.push(format!("Clamped to the {} limits {}..{}: {}", rarity.label(), limit.min, limit.max, limited));
+                priority = limited;
+            }
+        }
+        PriorityTrace { priority, deduction, steps }
+    }
+
+    // The highest priority a built card can have: a zero allocation still takes the smallest deduction off
+    // max_priority, which rarity limits may then raise
+    pub fn highest_priority(&self) -> i32 {
+        let rarities = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+        rarities.iter().map(|rarity| self.derive(0, rarity, 1.0).priority).max().unwrap_or(self.max_priority)
+    }
+}
+
+impl Config {
+    pub fn priority_trace(&self, allocation: i32, rarity: &Rarity) -> PriorityTrace {
+        self.priority.derive(allocation, rarity, self.power_to_priority.get_modifier(rarity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The rule the priority model replaced: DEFAULT_PRIORITY of 11 and even deductions moved up to the next odd number
+    fn baseline_deduction(allocation: i32, modifier: f32) -> i32 {
+        if allocation < 0 {
+            return 0;
+        }
+        let value = ((allocation as f32 * modifier) as i32).clamp(1, 11);
+        if value % 2 == 0 { value + 1 } else { value }
+    }
+
+    #[test]
+    fn default_model_matches_the_odd_number_rule() {
+        let model = PriorityModel::default();
+        for modifier in [0.5, 1.0, 1.25, 1.5, 1.75, 2.0] {
+            for allocation in -1..=12 {
+                let trace = model.derive(allocation, &Rarity::Rare, modifier);
+                assert_eq!(trace.deduction, baseline_deduction(allocation, modifier), "{} x {}", allocation, modifier);
+                assert_eq!(trace.priority, 11 - trace.deduction);
+            }
+        }
+    }
+
+    #[test]
+    fn rarity_limits_clamp_the_final_priority() {
+        let model = PriorityModel { rarity_limits: vec![RarityLimit { rarity: Rarity::Epic, min: 4, max: 6 }], ..PriorityModel::default() };
+        assert_eq!(model.derive(9, &Rarity::Epic, 1.0).priority, 4);
+        assert_eq!(model.derive(9, &Rarity::Rare, 1.0).priority, 2);
+        let nearest = PriorityModel { rounding: Rounding::Nearest, step: 1, offset: 0, ..PriorityModel::default() };
+        assert_eq!(nearest.derive(3, &Rarity::Rare, 1.5).deduction, 5);
+    }
+
+    #[test]
+    fn highest_priority_takes_the_smallest_deduction() {
+        let model = PriorityModel::default();
+        assert_eq!(model.highest_priority(), 10);
+        let offset = PriorityModel { offset: 0, ..PriorityModel::default() };
+        assert_eq!(offset.highest_priority(), 9);
+        let limited = PriorityModel { rarity_limits: vec![RarityLimit { rarity: Rarity::Common, min: 11, max: 11 }], ..PriorityModel::default() };
+        assert_eq!(limited.highest_priority(), 11);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{card::*, locale::*};
+
+const RARITIES: [Rarity; 5] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+
+impl Rarity {
+    // The rarity `steps` tiers above, or below when negative, None past Common or Legendary
+    pub fn shifted(&self, steps: i32) -> Option<Rarity> {
+        let index = RARITIES.iter().position(|rarity| rarity == self)? as i32 + steps;
+        RARITIES.get(usize::try_from(index).ok()?).cloned()
+    }
+}
+
+impl PowerRange {
+    pub fn average(&self) -> f32 {
+        (self.min + self.max) as f32 / 2.0
+    }
+}
+
+// Builds a card with a fixed power instead of a rolled one
+pub fn build_with_power(input: &CardInput, power: i32, config: &Config) -> Result<Card, String> {
+    let mut card = Card::new(input.name.clone(), input.rarity.clone(), input.efficiency.clone(), config.clone());
+    card.budget = power;
+    card.with_priority_allocation(input.priority_allocation)
+        .with_range(input.range.clone())
+        .with_conditional_effects(input.effects.clone(), input.conditionals.clone())
+        .build()
+}
+
+// The same card at another rarity, with the priority allocation that keeps its priority closest to the original
+// while still building on the rarity's lowest power roll, ties go to the allocation nearest the old one scaled by power
+pub fn retier(input: &CardInput, rarity: Rarity, config: &Config) -> CardInput {
+    let (old_power, new_power) = (config.rarity_ranges.get(&input.rarity), config.rarity_ranges.get(&rarity));
+    let target = config.priority_trace(input.priority_allocation, &input.rarity).priority;
+    let scaled = (input.priority_allocation as f32 * new_power.average() / old_power.average().max(1.0)).round() as i32;
+    let candidate = |allocation: i32| CardInput { rarity: rarity.clone(), priority_allocation: allocation, ..input.clone() };
+    let allocation = (1..new_power.min.max(2))
+        .min_by_key(|allocation| {
+            let fails = build_with_power(&candidate(*allocation), new_power.min, config).is_err();
+            (fails, (config.priority_trace(*allocation, &rarity).priority - target).abs(), (allocation - scaled).abs())
+        })
+        .unwrap_or(1);
+    candidate(allocation)
+}
+
+fn describe_rolls(input: &CardInput, config: &Config, describe: impl Fn(&Card) -> String) -> String {
+    let power = config.rarity_ranges.get(&input.rarity);
+    let rolls: Vec<String> = [power.min, power.max]
+        .iter()
+        .map(|power| build_with_power(input, *power, config).map_or_else(|err| format!("error: {}", err), |card| describe(&card)))
+        .collect();
+    if rolls[0] == rolls[1] { rolls[0].clone() } else { rolls.join(" / ") }
+}
+
+// Old and new stats side by side, values that differ between the low and high power roll are shown as low / high
+pub fn compare_cards(before: &CardInput, after: &CardInput, config: &Config) -> String {
+    let columns = |input: &CardInput| {
+        let power = config.rarity_ranges.get(&input.rarity);
+        vec![
+            input.rarity.label(),
+            if power.min == power.max { power.min.to_string() } else { format!("{} / {}", power.min, power.max) },
+            input.priority_allocation.to_string(),
+            config.priority_trace(input.priority_allocation, &input.rarity).priority.to_string(),
+            describe_rolls(input, config, |card| card.barnacles.to_string()),
+            describe_rolls(input, config, |card| card.get_withdraw().to_string()),
+            describe_rolls(input, config, |card| card.effects.iter().map(|effect| config.describe_effect(effect)).collect::<Vec<String>>().join(" + ")),
+        ]
+    };
+    let labels = ["retier-rarity", "retier-power", "retier-priority-allocation", "retier-priority", "retier-cast", "retier-withdraw", "retier-effects"].map(|key| tr(key, &[]));
+    let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0) + 3;
+    let (old, new) = (columns(before), columns(after));
+    let width = old.iter().map(|column| column.chars().count()).max().unwrap_or(0) + 4;
+    let mut lines = vec![format!("{}:", before.name)];
+    for ((label, old), new) in labels.into_iter().zip(old).zip(new) {
+        lines.push(format!("\t{}{}{}", pad_right(label, label_width, ' '), pad_right(old, width, ' '), new));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rarities_shift_within_the_tiers() {
+        assert_eq!(Rarity::Rare.shifted(1), Some(Rarity::Epic));
+        assert_eq!(Rarity::Rare.shifted(-2), Some(Rarity::Common));
+        assert_eq!(Rarity::Legendary.shifted(1), None);
+        assert_eq!(Rarity::Common.shifted(-1), None);
+    }
+
+    #[test]
+    fn retiered_cards_build_on_every_roll() {
+        let config = Config::default();
+        for input in DeckInputs::new(DeckType::Journeyman).inputs.iter() {
+            for rarity in [Rarity::Common, Rarity::Legendary] {
+                let proposal = retier(input, rarity.clone(), &config);
+                assert_eq!((proposal.rarity.clone(), &proposal.name, proposal.effects.len()), (rarity.clone(), &input.name, input.effects.len()));
+                let power = config.rarity_ranges.get(&rarity);
+                assert!(build_with_power(&proposal, power.min, &config).is_ok(), "{} as {:?}", input.name, rarity);
+                assert!(build_with_power(&proposal, power.max, &config).is_ok(), "{} as {:?}", input.name, rarity);
+            }
+        }
+    }
+
+    #[test]
+    fn comparison_lists_old_and_new_side_by_side() {
+        let config = Config::default();
+        let input = CardInput::new(Rarity::Rare);
+        let comparison = compare_cards(&input, &retier(&input, Rarity::Epic, &config), &config);
+        let lines: Vec<&str> = comparison.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[1].starts_with("\tRarity") && lines[1].contains("Rare") && lines[1].ends_with("Epic"));
+    }
+}
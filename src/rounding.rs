@@ -0,0 +1,235 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// How multiplied budgets and prices become whole numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RoundingPolicy {
+    #[default]
+    #[serde(alias = "floor")]
+    Floor,
+    #[serde(alias = "half_even")]
+    HalfEven,
+    #[serde(alias = "ceil")]
+    Ceil,
+    // Budgets are carried as exact fractions, only magnitudes and barnacles are floored
+    #[serde(alias = "exact")]
+    Exact,
+}
+
+impl RoundingPolicy {
+    pub fn round(&self, value: f32) -> i32 {
+        match self {
+            RoundingPolicy::Floor | RoundingPolicy::Exact => value.floor() as i32,
+            RoundingPolicy::HalfEven => value.round_ties_even() as i32,
+            RoundingPolicy::Ceil => value.ceil() as i32,
+        }
+    }
+
+    pub fn scale(&self, value: Fraction, multiplier: f32) -> Fraction {
+        match self {
+            RoundingPolicy::Exact => value * Fraction::approximate(multiplier),
+            _ => Fraction::from(self.round(value.floor() as f32 * multiplier)),
+        }
+    }
+
+    pub fn divide(&self, value: Fraction, divisor: f32) -> Fraction {
+        match self {
+            RoundingPolicy::Exact => value / Fraction::approximate(divisor),
+            _ => Fraction::from(self.round(value.floor() as f32 * (1.0 / divisor))),
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs().max(1) } else { gcd(b, a % b) }
+}
+
+// Denominators are kept small enough that budgets never overflow
+const MAX_DENOMINATOR: i64 = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fraction {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Fraction {
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        let sign = if denominator < 0 { -1 } else { 1 };
+        let divisor = gcd(numerator, denominator);
+        Fraction { numerator: sign * numerator / divisor, denominator: sign * denominator / divisor }
+    }
+
+    // Closest fraction to `value` with a denominator of at most MAX_DENOMINATOR, so 0.33333334 becomes 1/3
+    pub fn approximate(value: f32) -> Self {
+        let value = value as f64;
+        let (mut previous, mut current) = ((0i64, 1i64), (1i64, 0i64));
+        let mut remainder = value;
+        for _ in 0..32 {
+            let whole = remainder.floor();
+            let next = (whole as i64 * current.0 + previous.0, whole as i64 * current.1 + previous.1);
+            if next.1 > MAX_DENOMINATOR {
+                break;
+            }
+            (previous, current) = (current, next);
+            if (remainder - whole).abs() < 1e-9 || (current.0 as f64 / current.1 as f64 - value).abs() < 1e-7 {
+                break;
+            }
+            remainder = 1.0 / (remainder - whole);
+        }
+        Fraction::new(current.0, current.1.max(1))
+    }
+
+    pub fn zero() -> Self {
+        Fraction::from(0)
+    }
+
+    pub fn floor(&self) -> i32 {
+        self.numerator.div_euclid(self.denominator) as i32
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.numerator as f32 / self.denominator as f32
+    }
+}
+
+impl Default for Fraction {
+    fn default() -> Self {
+        Fraction::zero()
+    }
+}
+
+impl From<i32> for Fraction {
+    fn from(value: i32) -> Self {
+        Fraction { numerator: value as i64, denominator: 1 }
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+    fn add(self, other: Fraction) -> Fraction {
+        Fraction::new(self.numerator * other.denominator + other.numerator * self.denominator, self.denominator * other.denominator)
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+    fn sub(self, other: Fraction) -> Fraction {
+        Fraction::new(self.numerator * other.denominator - other.numerator * self.denominator, self.denominator * other.denominator)
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+    fn mul(self, other: Fraction) -> Fraction {
+        Fraction::new(self.numerator * other.numerator, self.denominator * other.denominator)
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+    fn div(self, other: Fraction) -> Fraction {
+        if other.numerator == 0 {
+            return Fraction::zero();
+        }
+        Fraction::new(self.numerator * other.denominator, self.denominator * other.numerator)
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Fraction) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Fraction) -> std::cmp::Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl std::fmt::Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+// What a card's effects were given against what they are exactly worth and what was charged for them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundingLoss {
+    pub available: Fraction,
+    pub exact: Fraction,
+    pub charged: Fraction,
+}
+
+impl RoundingLoss {
+    // Budget that bought nothing
+    pub fn lost(&self) -> Fraction {
+        self.available - self.exact
+    }
+
+    // Positive when effects were charged more than they are worth
+    pub fn mispriced(&self) -> Fraction {
+        self.charged - self.exact
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions_stay_reduced_and_exact() {
+        assert_eq!(Fraction::new(6, -4), Fraction::new(-3, 2));
+        assert_eq!(Fraction::approximate(1.0 / 3.0), Fraction::new(1, 3));
+        assert_eq!(Fraction::approximate(0.875), Fraction::new(7, 8));
+        let third = Fraction::new(1, 3);
+        assert_eq!(third + third + third, Fraction::from(1));
+        assert_eq!((Fraction::from(7) / Fraction::new(7, 8)).to_string(), "8");
+        assert_eq!(Fraction::new(-1, 2).floor(), -1);
+        assert_eq!(Fraction::from(3) / Fraction::zero(), Fraction::zero());
+        assert!(Fraction::new(2, 3) > Fraction::new(3, 5));
+    }
+
+    #[test]
+    fn policies_round_the_same_value_differently() {
+        let rounded = |value: f32| [RoundingPolicy::Floor, RoundingPolicy::HalfEven, RoundingPolicy::Ceil, RoundingPolicy::Exact].map(|policy| policy.round(value));
+        assert_eq!(rounded(2.5), [2, 2, 3, 2]);
+        assert_eq!(rounded(3.5), [3, 4, 4, 3]);
+        assert_eq!(RoundingPolicy::Floor.divide(Fraction::from(7), 0.875), Fraction::from(8));
+        assert_eq!(RoundingPolicy::Exact.scale(Fraction::new(5, 2), 1.5), Fraction::new(15, 4));
+        assert_eq!(RoundingPolicy::Floor.scale(Fraction::new(5, 2), 1.5), Fraction::from(3));
+    }
+
+    #[test]
+    fn rounding_losses_compare_against_exact_worth() {
+        let loss = RoundingLoss { available: Fraction::from(7), exact: Fraction::new(13, 2), charged: Fraction::from(7) };
+        assert_eq!((loss.lost(), loss.mispriced()), (Fraction::new(1, 2), Fraction::new(1, 2)));
+    }
+
+    #[test]
+    fn rounding_up_never_overspends_the_budget() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::card::*;
+
+        for rounding in [RoundingPolicy::Ceil, RoundingPolicy::HalfEven] {
+            let config = Config { rounding, ..Config::default() };
+            for (rarity, range) in [(Rarity::Common, "Single"), (Rarity::Rare, "Multiple"), (Rarity::Legendary, "AoE")] {
+                let mut input = CardInput::new(rarity);
+                input.range = Range::new(range);
+                input.effects = vec![Effect::new("AcidHeal")];
+                for seed in 0..8 {
+                    let loss = input.build(&config, &mut StdRng::seed_from_u64(seed)).unwrap().rounding_loss;
+                    assert!(loss.charged <= loss.available, "{} under {:?}: charged {} of {}", range, rounding, loss.charged, loss.available);
+                }
+            }
+        }
+    }
+}
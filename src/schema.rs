@@ -0,0 +1,173 @@
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, NumberValidation, Schema, SchemaObject},
+    schema_for,
+};
+use serde_json::{json, Value};
+
+use crate::{card::*, catalog::*};
+
+// Multipliers are divided by when pricing effects, so zero is not allowed
+pub fn positive_number(_: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Number.into()),
+        number: Some(Box::new(NumberValidation {
+            exclusive_minimum: Some(0.0),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+// `TargetShielded` -> `target_shielded`, the lowercase alias every tagged variant is also read with
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && index > 0 {
+            snake.push('_');
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    snake
+}
+
+// Catalog ids are matched like `same_id`, so `acid_heal` and `Acid Heal` are accepted as well as `AcidHeal`
+fn id_schema(ids: Vec<String>) -> Value {
+    let alternatives: Vec<String> = ids
+        .iter()
+        .map(|id| {
+            let chars: Vec<String> = normalise_id(id)
+                .chars()
+                .map(|ch| match ch {
+                    _ if ch.is_ascii_alphabetic() => format!("[{}{}]", ch, ch.to_ascii_uppercase()),
+                    _ if ch.is_alphanumeric() => ch.to_string(),
+                    _ => format!("\\{}", ch),
+                })
+                .collect();
+            chars.join("[_ ]*")
+        })
+        .collect();
+    let pattern = format!("^[_ ]*(?:{})[_ ]*$", alternatives.join("|"));
+    json!({ "type": "string", "anyOf": [{ "enum": ids }, { "pattern": pattern }] })
+}
+
+// Ids the loaded config's catalogs define for the definitions that name a catalog entry
+fn catalog_ids(definition: &str, config: &Config) -> Option<Vec<String>> {
+    match definition {
+        "Effect" => Some(config.effect_catalog.iter().map(|definition| definition.id.clone()).collect()),
+        "Range" => Some(config.range_catalog.iter().map(|definition| definition.id.clone()).collect()),
+        "Efficiency" => Some(config.efficiency.iter().map(|tier| tier.id.clone()).collect()),
+        _ => None,
+    }
+}
+
+// Restricts catalog ids to the config's catalogs, adds the lowercase aliases of tagged variants, and accepts the
+// shorthand strings `expand_shorthand` reads: catalog ids and the names of variants without fields
+fn with_catalogs(mut schema: Value, config: &Config) -> Value {
+    let Some(Value::Object(definitions)) = schema.get_mut("definitions") else {
+        return schema;
+    };
+    for (name, definition) in definitions.iter_mut() {
+        if let Some(ids) = catalog_ids(name, config) {
+            definition["properties"]["type"] = id_schema(ids.clone());
+            *definition = json!({ "oneOf": [definition.clone(), id_schema(ids)] });
+            continue;
+        }
+        let names = if let Some(Value::Array(variants)) = definition.get_mut("oneOf") {
+            let mut names = Vec::new();
+            for variant in variants.iter_mut() {
+                let Some(Value::Array(tags)) = variant.pointer_mut("/properties/type/enum") else {
+                    continue;
+                };
+                let tag_names: Vec<String> = tags.iter().filter_map(Value::as_str).map(String::from).collect();
+                let aliases: Vec<String> = tag_names.iter().map(|tag| snake_case(tag)).filter(|alias| !tag_names.contains(alias)).collect();
+                tags.extend(aliases.iter().cloned().map(Value::from));
+                if variant.get("required") == Some(&json!(["type"])) {
+                    names.extend(tag_names.into_iter().chain(aliases));
+                }
+            }
+            names
+        } else {
+            continue;
+        };
+        if !names.is_empty() {
+            *definition = json!({ "oneOf": [definition.clone(), { "type": "string", "enum": names }] });
+        }
+    }
+    schema
+}
+
+pub fn deck_schema(config: &Config) -> Value {
+    with_catalogs(serde_json::to_value(schema_for!(DeckInputs)).unwrap_or_default(), config)
+}
+
+pub fn config_schema(config: &Config) -> Value {
+    with_catalogs(serde_json::to_value(schema_for!(Config)).unwrap_or_default(), config)
+}
+
+pub fn write_schemas(folder: Option<String>, config: &Config) {
+    let folder = folder.unwrap_or(String::from("schemas"));
+    if std::fs::create_dir_all(&folder).is_err() {
+        println!("Could not create folder: {}", folder);
+        return;
+    }
+    for (name, schema) in [("deck", deck_schema(config)), ("config", config_schema(config))] {
+        let path = format!("{}/{}.schema.json", folder, name);
+        let Ok(schema_buf) = serde_json::to_string_pretty(&schema) else {
+            println!("Bad type!");
+            return;
+        };
+        if std::fs::write(&path, schema_buf).is_err() {
+            println!("Could not write to file: {}", path);
+            return;
+        }
+        println!("Wrote {}", path);
+    }
+    let url = if std::path::Path::new(&folder).is_absolute() { folder.clone() } else { format!("./{}", folder) };
+    println!("To validate in VS Code, add to .vscode/settings.json:");
+    println!("  \"files.associations\": {{ \"*.deck\": \"json\" }},");
+    println!("  \"json.schemas\": [");
+    println!("    {{ \"fileMatch\": [\"*.deck\"], \"url\": \"{}/deck.schema.json\" }},", url);
+    println!("    {{ \"fileMatch\": [\"config.json\"], \"url\": \"{}/config.schema.json\" }}", url);
+    println!("  ]");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_ids_are_listed_and_accepted_as_shorthand() {
+        let config = Config::default();
+        let schema = deck_schema(&config);
+        let effect = &schema["definitions"]["Effect"]["oneOf"];
+        let ids: Vec<Value> = config.effect_catalog.iter().map(|definition| Value::from(definition.id.clone())).collect();
+        assert_eq!(effect[0]["properties"]["type"]["anyOf"][0]["enum"], Value::Array(ids.clone()));
+        assert_eq!(effect[1]["anyOf"][0]["enum"], Value::Array(ids));
+        assert!(effect[1]["anyOf"][1]["pattern"].as_str().unwrap().contains("[aA][_ ]*[cC][_ ]*[iI][_ ]*[dD][_ ]*[hH]"));
+    }
+
+    #[test]
+    fn unit_variants_are_accepted_by_name_and_alias() {
+        let schema = deck_schema(&Config::default());
+        assert_eq!(schema["definitions"]["Rarity"]["oneOf"][1]["enum"], json!(["Common", "common", "Uncommon", "uncommon", "Rare", "rare", "Epic", "epic", "Legendary", "legendary"]));
+        assert_eq!(schema["definitions"]["Condition"]["oneOf"][1]["enum"], json!(["TargetShielded", "target_shielded", "OnWithdraw", "on_withdraw"]));
+        assert_eq!(schema["definitions"]["CardInput"]["properties"]["effects"]["minItems"], json!(1));
+    }
+
+    #[test]
+    fn multipliers_must_be_positive() {
+        let schema = config_schema(&Config::default());
+        let definitions = &schema["definitions"];
+        assert_eq!(definitions["RarityPriorityModifiers"]["properties"]["epic"]["exclusiveMinimum"], json!(0.0));
+        assert_eq!(definitions["EffectDefinition"]["properties"]["barnacle_multiplier"]["exclusiveMinimum"], json!(0.0));
+    }
+
+    #[test]
+    fn decks_hold_five_cards_with_some_priority_allocated() {
+        let schema = deck_schema(&Config::default());
+        assert_eq!((&schema["properties"]["inputs"]["minItems"], &schema["properties"]["inputs"]["maxItems"]), (&json!(5), &json!(5)));
+        assert_eq!(schema["definitions"]["CardInput"]["properties"]["priority_allocation"]["minimum"], json!(1.0));
+    }
+}
@@ -0,0 +1,742 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, catalog::*, conditions::*, strategy::*};
+
+// Match rules used to play decks against each other:
+// - Each side has a party of members and a hand drawn from its shuffled deck
+// - Every turn both sides gain barnacles, draw a card and pick one action (cast, withdraw or pass)
+// - Casts resolve in priority order (lowest first), cast cards go to the discard pile
+// - Ranges reach a fixed number of members, the room or the whole party, random ranges pick their targets at random,
+//   lines reach members side by side from the front of the party and cones the members beside their target
+// - Caster ranges only reach the first living member of the casting side and ally ranges the whole casting side,
+//   every effect aimed at enemies (damage, corrosion, control, draining and discarding) lands on the casting side instead
+// - Withdrawing pays a card's withdraw cost to return it from the discard pile to the hand
+// - Over time effects (poison, regeneration, timed shields) tick again at the start of later turns
+// - Stunned sides cannot act, slowed sides cast after their opponent, silenced sides cannot withdraw
+//   and damage against a taunting side hits its healthiest members first
+// - Resource effects gain barnacles, drain the opponent's barnacles or discount the side's next cast
+// - Card flow effects draw, make the opponent discard at random, return the most expensive cast cards
+//   from the discard pile or search the draw pile for a rarity
+// - Conditional effects resolve after the card's own effects when their condition held as it was cast,
+//   priority conditions compare against the card the opponent cast the same turn,
+//   withdraw triggers resolve as the card returns to the hand
+// - A side loses when all of its members are defeated, otherwise the healthiest side wins at the turn limit
+#[derive(Debug, Deserialize, Clone, Serialize, JsonSchema)]
+pub struct MatchRules {
+    #[schemars(range(min = 1))]
+    pub party_size: usize,
+    pub room_size: usize,
+    #[schemars(range(min = 1))]
+    pub member_health: i32,
+    #[schemars(range(min = 0))]
+    pub starting_barnacles: i32,
+    #[schemars(range(min = 0))]
+    pub barnacles_per_turn: i32,
+    pub opening_hand: usize,
+    #[schemars(range(min = 1))]
+    pub max_turns: i32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            party_size: 4,
+            room_size: 3,
+            member_health: 12,
+            starting_barnacles: 2,
+            barnacles_per_turn: 3,
+            opening_hand: 3,
+            max_turns: 30,
+        }
+    }
+}
+
+// How members are picked once the effect knows how many it reaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    // The members the effect favours, such as the weakest for damage
+    Favoured,
+    Random,
+    // Members side by side from the front of the party
+    Line,
+    // The favoured member and up to `spread` members on either side of it
+    Cone { spread: usize },
+    Caster,
+    Allies,
+}
+
+impl Pattern {
+    pub fn own_side_only(&self) -> bool {
+        matches!(self, Pattern::Caster | Pattern::Allies)
+    }
+}
+
+// How many members an effect reaches and how they are picked
+#[derive(Debug, Clone, Copy)]
+pub struct Reach {
+    pub count: usize,
+    pub pattern: Pattern,
+}
+
+impl MatchRules {
+    pub fn reach(&self, targets: &Targets) -> Reach {
+        let (count, pattern) = match targets {
+            Targets::Count { count } => (*count, Pattern::Favoured),
+            Targets::Random { count } => (*count, Pattern::Random),
+            Targets::Room => (self.room_size, Pattern::Favoured),
+            Targets::Party => (self.party_size, Pattern::Favoured),
+            Targets::Caster => (1, Pattern::Caster),
+            Targets::Allies => (self.party_size, Pattern::Allies),
+            Targets::Line { count } => (*count, Pattern::Line),
+            Targets::Cone { spread } => (1 + 2 * spread, Pattern::Cone { spread: *spread }),
+        };
+        Reach { count: count.min(self.party_size), pattern }
+    }
+
+    // Cards with a missing or unknown range reach a single member
+    pub fn card_reach(&self, card: &Card) -> Reach {
+        card.range
+            .as_ref()
+            .and_then(|range| card.config.range_definition(range))
+            .map_or(Reach { count: 1, pattern: Pattern::Favoured }, |definition| self.reach(&definition.targets))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub health: i32,
+    pub shield: i32,
+    // (amount, turn it expires on), absorbed before the regular shield
+    pub timed_shields: Vec<(i32, i32)>,
+}
+
+impl Member {
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    pub fn total_shield(&self) -> i32 {
+        self.shield + self.timed_shields.iter().map(|(amount, _)| amount).sum::<i32>()
+    }
+
+    fn take_damage(&mut self, mut amount: i32) {
+        for (shield, _) in self.timed_shields.iter_mut() {
+            let absorbed = amount.min(*shield);
+            *shield -= absorbed;
+            amount -= absorbed;
+        }
+        self.timed_shields.retain(|(shield, _)| *shield > 0);
+        let absorbed = amount.min(self.shield);
+        self.shield -= absorbed;
+        self.health -= amount - absorbed;
+    }
+}
+
+// An over time effect still ticking after the turn it was cast on
+#[derive(Debug, Clone)]
+pub struct OngoingEffect {
+    pub effect: Effect,
+    pub mechanic: Mechanic,
+    pub targets: Reach,
+    pub cast_turn: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Side {
+    pub members: Vec<Member>,
+    pub barnacles: i32,
+    // Taken off the cost of the next card cast
+    pub discount: i32,
+    pub hand: Vec<Card>,
+    pub draw_pile: Vec<Card>,
+    pub discard: Vec<Card>,
+    pub ongoing: Vec<OngoingEffect>,
+    // Control effects on this side and the last turn they apply to
+    pub conditions: Vec<(Mechanic, i32)>,
+}
+
+impl Side {
+    pub fn new(mut deck: Vec<Card>, rules: &MatchRules, rng: &mut StdRng) -> Self {
+        deck.shuffle(rng);
+        let hand = deck.split_off(deck.len().saturating_sub(rules.opening_hand));
+        Side {
+            members: vec![Member { health: rules.member_health, shield: 0, timed_shields: Vec::new() }; rules.party_size],
+            barnacles: rules.starting_barnacles,
+            discount: 0,
+            hand,
+            draw_pile: deck,
+            discard: Vec::new(),
+            ongoing: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    fn draw(&mut self, rng: &mut StdRng) {
+        if self.draw_pile.is_empty() {
+            self.draw_pile.append(&mut self.discard);
+            self.draw_pile.shuffle(rng);
+        }
+        if let Some(card) = self.draw_pile.pop() {
+            self.hand.push(card);
+        }
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.members.iter().all(|member| !member.is_alive())
+    }
+
+    pub fn total_health(&self) -> i32 {
+        self.members.iter().map(|member| member.health.max(0)).sum()
+    }
+
+    pub fn missing_health(&self, rules: &MatchRules) -> i32 {
+        self.members
+            .iter()
+            .filter(|member| member.is_alive())
+            .map(|member| rules.member_health - member.health)
+            .sum()
+    }
+
+    pub fn is_under(&self, condition: Mechanic) -> bool {
+        self.conditions.iter().any(|(mechanic, _)| *mechanic == condition)
+    }
+
+    pub fn can_cast(&self, index: usize) -> bool {
+        !self.is_under(Mechanic::Stun)
+            && self.hand.get(index).is_some_and(|card| self.cast_cost(card) <= self.barnacles)
+    }
+
+    pub fn cast_cost(&self, card: &Card) -> i32 {
+        (card.barnacles - self.discount).max(0)
+    }
+
+    pub fn can_withdraw(&self, index: usize) -> bool {
+        !(self.is_under(Mechanic::Stun) || self.is_under(Mechanic::Silence))
+            && self.discard.get(index).is_some_and(|card| card.get_withdraw() <= self.barnacles)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Cast(usize),
+    Withdraw(usize),
+    Pass,
+}
+
+pub struct TurnView<'a> {
+    pub turn: i32,
+    pub rules: &'a MatchRules,
+    pub own: &'a Side,
+    pub opponent: &'a Side,
+}
+
+impl TurnView<'_> {
+    pub fn castable(&self) -> Vec<usize> {
+        (0..self.own.hand.len()).filter(|index| self.own.can_cast(*index)).collect()
+    }
+
+    pub fn withdrawable(&self) -> Vec<usize> {
+        (0..self.own.discard.len()).filter(|index| self.own.can_withdraw(*index)).collect()
+    }
+}
+
+// Total magnitude a card applies across all of its effects and targets
+pub fn effect_value(card: &Card, rules: &MatchRules) -> i32 {
+    let targets = rules.card_reach(card).count as i32;
+    card.effects
+        .iter()
+        .map(|effect| match card.config.mechanic(effect) {
+            Some(mechanic) if mechanic.is_side_wide() => effect.magnitude(),
+            _ => effect.total_magnitude() * targets,
+        })
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchResult {
+    pub winner: Option<usize>,
+    pub turns: i32,
+}
+
+pub fn play_match(decks: [Vec<Card>; 2], strategies: [&mut dyn Strategy; 2], rules: &MatchRules, rng: &mut StdRng) -> MatchResult {
+    let [deck_a, deck_b] = decks;
+    let mut sides = [Side::new(deck_a, rules, rng), Side::new(deck_b, rules, rng)];
+    let [strategy_a, strategy_b] = strategies;
+    for turn in 1..=rules.max_turns {
+        for side in sides.iter_mut() {
+            side.barnacles += rules.barnacles_per_turn;
+            if turn > 1 {
+                side.draw(rng);
+            }
+        }
+        for caster in 0..2 {
+            tick_ongoing(&mut sides, caster, turn, rules, rng);
+        }
+        match (sides[0].is_defeated(), sides[1].is_defeated()) {
+            (true, true) => return MatchResult { winner: None, turns: turn },
+            (true, false) => return MatchResult { winner: Some(1), turns: turn },
+            (false, true) => return MatchResult { winner: Some(0), turns: turn },
+            (false, false) => {}
+        }
+        let actions = [
+            strategy_a.choose(&TurnView { turn, rules, own: &sides[0], opponent: &sides[1] }, rng),
+            strategy_b.choose(&TurnView { turn, rules, own: &sides[1], opponent: &sides[0] }, rng),
+        ];
+
+        let mut casts = Vec::new();
+        for (caster, action) in actions.into_iter().enumerate() {
+            match action {
+                Action::Withdraw(index) if sides[caster].can_withdraw(index) => {
+                    let card = sides[caster].discard.remove(index);
+                    sides[caster].barnacles -= card.get_withdraw();
+                    resolve_withdraw(&mut sides, caster, &card, turn, rules, rng);
+                    sides[caster].hand.push(card);
+                }
+                Action::Cast(index) if sides[caster].can_cast(index) => casts.push((caster, index)),
+                _ => {}
+            }
+        }
+        let priority = |(caster, index): &(usize, usize)| {
+            let slowed = sides[*caster].is_under(Mechanic::Slow);
+            (slowed, sides[*caster].hand[*index].priority)
+        };
+        casts.sort_by_key(priority);
+        if casts.len() == 2 && priority(&casts[0]) == priority(&casts[1]) && rng.gen_bool(0.5) {
+            casts.swap(0, 1);
+        }
+
+        // Both casts leave the hand and are paid for before either resolves, so discards and drains only affect later turns
+        let casts: Vec<(usize, Card)> = casts
+            .into_iter()
+            .map(|(caster, index)| {
+                let card = sides[caster].hand.remove(index);
+                sides[caster].barnacles -= sides[caster].cast_cost(&card);
+                sides[caster].discount = 0;
+                (caster, card)
+            })
+            .collect();
+        let mut cast_priorities = [None, None];
+        for (caster, card) in casts.iter() {
+            cast_priorities[*caster] = Some(card.priority);
+        }
+        for (caster, card) in casts {
+            resolve_card(&mut sides, caster, &card, cast_priorities[1 - caster], turn, rules, rng);
+            sides[caster].discard.push(card);
+            if sides[1 - caster].is_defeated() {
+                return MatchResult { winner: Some(caster), turns: turn };
+            }
+        }
+    }
+    let (health_a, health_b) = (sides[0].total_health(), sides[1].total_health());
+    MatchResult {
+        winner: match health_a.cmp(&health_b) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        },
+        turns: rules.max_turns,
+    }
+}
+
+fn pick_targets(members: &[Member], reach: Reach, rng: &mut StdRng, key: impl Fn(&Member) -> i32) -> Vec<usize> {
+    let mut alive: Vec<usize> = (0..members.len()).filter(|index| members[*index].is_alive()).collect();
+    match reach.pattern {
+        Pattern::Favoured => alive.sort_by_key(|index| key(&members[*index])),
+        Pattern::Random => alive.shuffle(rng),
+        Pattern::Line | Pattern::Caster | Pattern::Allies => {}
+        Pattern::Cone { spread } => {
+            let Some(centre) = (0..alive.len()).min_by_key(|position| key(&members[alive[*position]])) else {
+                return alive;
+            };
+            let (first, last) = (centre.saturating_sub(spread), (centre + spread).min(alive.len() - 1));
+            return alive[first..=last].to_vec();
+        }
+    }
+    alive.truncate(reach.count);
+    alive
+}
+
+fn split_sides(sides: &mut [Side; 2], caster: usize) -> (&mut Side, &mut Side) {
+    let (first, second) = sides.split_at_mut(1);
+    if caster == 0 { (&mut first[0], &mut second[0]) } else { (&mut second[0], &mut first[0]) }
+}
+
+// Damage focuses the weakest enemies, healing and shields go to the weakest allies.
+// Acid healing heals allies and corrodes the shields of the most protected enemies.
+// Over time effects tick once when cast, the rest of their ticks happen at the start of later turns.
+fn resolve_card(sides: &mut [Side; 2], caster: usize, card: &Card, opposing_priority: Option<i32>, turn: i32, rules: &MatchRules, rng: &mut StdRng) {
+    let (own, opponent) = split_sides(sides, caster);
+    let fired = fired_payloads(card, |condition| match *condition {
+        Condition::PriorityAbove { priority } => opposing_priority.is_some_and(|opposing| opposing > priority),
+        Condition::TargetShielded => opponent.members.iter().any(|member| member.is_alive() && member.total_shield() > 0),
+        Condition::OnWithdraw => false,
+    });
+    let effects: Vec<Effect> = card.effects.iter().cloned().chain(fired).collect();
+    resolve_effects(own, opponent, &effects, card, turn, rules, rng);
+}
+
+fn resolve_withdraw(sides: &mut [Side; 2], caster: usize, card: &Card, turn: i32, rules: &MatchRules, rng: &mut StdRng) {
+    let (own, opponent) = split_sides(sides, caster);
+    let fired = fired_payloads(card, |condition| matches!(condition, Condition::OnWithdraw));
+    resolve_effects(own, opponent, &fired, card, turn, rules, rng);
+}
+
+fn fired_payloads(card: &Card, holds: impl Fn(&Condition) -> bool) -> Vec<Effect> {
+    card.conditionals
+        .iter()
+        .filter(|conditional| holds(&conditional.condition))
+        .filter_map(|conditional| conditional.payload(&card.effects))
+        .collect()
+}
+
+fn resolve_effects(own: &mut Side, opponent: &mut Side, effects: &[Effect], card: &Card, turn: i32, rules: &MatchRules, rng: &mut StdRng) {
+    let reach = rules.card_reach(card);
+    for effect in effects.iter() {
+        let Some(mechanic) = card.config.mechanic(effect) else {
+            continue;
+        };
+        resolve_effect(own, opponent, effect, mechanic, reach, turn, rules, rng);
+        if effect.ticks() > 1 {
+            own.ongoing.push(OngoingEffect { effect: effect.clone(), mechanic, targets: reach, cast_turn: turn });
+        }
+    }
+}
+
+fn tick_ongoing(sides: &mut [Side; 2], caster: usize, turn: i32, rules: &MatchRules, rng: &mut StdRng) {
+    let (own, opponent) = split_sides(sides, caster);
+    for member in own.members.iter_mut() {
+        member.timed_shields.retain(|(_, expires)| *expires > turn);
+    }
+    own.conditions.retain(|(_, last_turn)| *last_turn >= turn);
+    let ongoing = std::mem::take(&mut own.ongoing);
+    for effect in ongoing.iter() {
+        let Some((duration, tick)) = effect.effect.timing() else {
+            continue;
+        };
+        let elapsed = turn - effect.cast_turn;
+        if elapsed < duration && elapsed % tick.max(1) == 0 {
+            resolve_effect(own, opponent, &effect.effect, effect.mechanic, effect.targets, effect.cast_turn, rules, rng);
+        }
+    }
+    own.ongoing = ongoing
+        .into_iter()
+        .filter(|effect| effect.effect.timing().is_some_and(|(duration, _)| turn - effect.cast_turn < duration))
+        .collect();
+}
+
+fn heal(side: &mut Side, reach: Reach, magnitude: i32, rules: &MatchRules, rng: &mut StdRng) {
+    for index in pick_targets(&side.members, reach, rng, |member| member.health) {
+        side.members[index].health = (side.members[index].health + magnitude).min(rules.member_health);
+    }
+}
+
+// Taunting sides draw damage to their healthiest members, a caster range only ever reaches the caster
+fn damage_targets(side: &Side, reach: Reach, rng: &mut StdRng) -> Vec<usize> {
+    if side.is_under(Mechanic::Taunt) && reach.pattern != Pattern::Caster {
+        pick_targets(&side.members, reach, rng, |member| -member.health)
+    } else {
+        pick_targets(&side.members, reach, rng, |member| member.health)
+    }
+}
+
+// Effects aimed at enemies land on the casting side when the range only reaches the casting side
+fn enemy_side<'a>(own: &'a mut Side, opponent: &'a mut Side, reach: Reach) -> &'a mut Side {
+    if reach.pattern.own_side_only() { own } else { opponent }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn resolve_effect(own: &mut Side, opponent: &mut Side, effect: &Effect, mechanic: Mechanic, reach: Reach, cast_turn: i32, rules: &MatchRules, rng: &mut StdRng) {
+    let magnitude = effect.magnitude;
+    let duration = effect.duration.unwrap_or(default_control_duration());
+    match mechanic {
+        Mechanic::Damage => {
+            let enemy = enemy_side(own, opponent, reach);
+            for index in damage_targets(enemy, reach, rng) {
+                enemy.members[index].take_damage(magnitude);
+            }
+        }
+        Mechanic::Heal | Mechanic::Regeneration => heal(own, reach, magnitude, rules, rng),
+        Mechanic::AcidHeal => {
+            heal(own, reach, magnitude, rules, rng);
+            let enemy = enemy_side(own, opponent, reach);
+            for index in pick_targets(&enemy.members, reach, rng, |member| -member.shield) {
+                enemy.members[index].shield = (enemy.members[index].shield - magnitude).max(0);
+            }
+        }
+        Mechanic::Shield => {
+            for index in pick_targets(&own.members, reach, rng, |member| member.total_shield()) {
+                own.members[index].shield += magnitude;
+            }
+        }
+        // Poison seeps past shields
+        Mechanic::Poison => {
+            let enemy = enemy_side(own, opponent, reach);
+            for index in damage_targets(enemy, reach, rng) {
+                enemy.members[index].health -= magnitude;
+            }
+        }
+        Mechanic::TimedShield => {
+            for index in pick_targets(&own.members, reach, rng, |member| member.total_shield()) {
+                own.members[index].timed_shields.push((magnitude, cast_turn + duration));
+            }
+        }
+        Mechanic::GainBarnacles => own.barnacles += magnitude,
+        Mechanic::DrainBarnacles => {
+            let enemy = enemy_side(own, opponent, reach);
+            enemy.barnacles = (enemy.barnacles - magnitude).max(0);
+        }
+        Mechanic::Discount => own.discount += magnitude,
+        Mechanic::Draw => {
+            for _ in 0..magnitude {
+                own.draw(rng);
+            }
+        }
+        Mechanic::Discard => {
+            let enemy = enemy_side(own, opponent, reach);
+            for _ in 0..magnitude.min(enemy.hand.len() as i32) {
+                let index = rng.gen_range(0..enemy.hand.len());
+                let card = enemy.hand.remove(index);
+                enemy.discard.push(card);
+            }
+        }
+        Mechanic::ReturnToHand => {
+            for _ in 0..magnitude {
+                let Some(index) = (0..own.discard.len()).max_by_key(|index| own.discard[*index].barnacles) else {
+                    break;
+                };
+                let card = own.discard.remove(index);
+                own.hand.push(card);
+            }
+        }
+        Mechanic::Search => {
+            let rarity = effect.rarity.clone().unwrap_or(default_search_rarity());
+            for _ in 0..magnitude {
+                let Some(index) = own.draw_pile.iter().position(|card| card.rarity == rarity) else {
+                    break;
+                };
+                let card = own.draw_pile.remove(index);
+                own.hand.push(card);
+            }
+        }
+        Mechanic::Taunt => own.conditions.push((mechanic, cast_turn + duration)),
+        Mechanic::Stun | Mechanic::Slow | Mechanic::Silence => enemy_side(own, opponent, reach).conditions.push((mechanic, cast_turn + duration)),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchupReport {
+    pub strategy: String,
+    pub opponent_strategy: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl MatchupReport {
+    pub fn win_rate(&self) -> f32 {
+        let games = self.wins + self.losses + self.draws;
+        if games == 0 {
+            0.0
+        } else {
+            self.wins as f32 / games as f32
+        }
+    }
+}
+
+// Plays every built-in strategy piloting the first deck against every built-in strategy piloting the second.
+// Decks are rebuilt for every game so power rolls vary, while the seed keeps runs reproducible.
+pub fn round_robin(decks: [&DeckInputs; 2], config: &Config, games: u32, seed: u64) -> Result<Vec<MatchupReport>, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut strategies = builtin_strategies();
+    let mut opponents = builtin_strategies();
+    let mut reports = Vec::new();
+    for strategy in strategies.iter_mut() {
+        for opponent in opponents.iter_mut() {
+            let mut report = MatchupReport {
+                strategy: strategy.name().to_string(),
+                opponent_strategy: opponent.name().to_string(),
+                wins: 0,
+                losses: 0,
+                draws: 0,
+            };
+            for _ in 0..games {
+                let cards = [decks[0].build(config, &mut rng)?, decks[1].build(config, &mut rng)?];
+                let result = play_match(cards, [strategy.as_mut(), opponent.as_mut()], &config.simulation, &mut rng);
+                match result.winner {
+                    Some(0) => report.wins += 1,
+                    Some(_) => report.losses += 1,
+                    None => report.draws += 1,
+                }
+            }
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Passive;
+
+    impl Strategy for Passive {
+        fn name(&self) -> &str {
+            "Passive"
+        }
+
+        fn choose(&mut self, _view: &TurnView, _rng: &mut StdRng) -> Action {
+            Action::Pass
+        }
+    }
+
+    #[test]
+    fn round_robin_plays_every_pairing_reproducibly() {
+        let deck = DeckInputs::new(DeckType::Starter);
+        let reports = round_robin([&deck, &deck], &Config::default(), 3, 7).unwrap();
+        assert_eq!(reports.len(), builtin_strategies().len().pow(2));
+        assert!(reports.iter().all(|report| report.wins + report.losses + report.draws == 3));
+        let again = round_robin([&deck, &deck], &Config::default(), 3, 7).unwrap();
+        let results = |reports: &[MatchupReport]| reports.iter().map(|report| (report.wins, report.losses, report.draws)).collect::<Vec<_>>();
+        assert_eq!(results(&reports), results(&again));
+    }
+
+    #[test]
+    fn custom_strategies_can_be_plugged_in() {
+        let config = Config::default();
+        let deck = DeckInputs::new(DeckType::Starter);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..5 {
+            let cards = [deck.build(&config, &mut rng).unwrap(), deck.build(&config, &mut rng).unwrap()];
+            let result = play_match(cards, [&mut Passive, &mut GreedyDamage], &config.simulation, &mut rng);
+            assert_ne!(result.winner, Some(0));
+            assert!(result.turns <= config.simulation.max_turns);
+        }
+    }
+
+    #[test]
+    fn stunned_sides_cannot_act_and_silenced_sides_cannot_withdraw() {
+        let config = Config::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let cards = DeckInputs::new(DeckType::Starter).build(&config, &mut rng).unwrap();
+        let mut side = Side::new(cards, &config.simulation, &mut rng);
+        side.barnacles = 1000;
+        side.discard.push(side.hand.pop().unwrap());
+        assert!(side.can_cast(0) && side.can_withdraw(0));
+        side.conditions.push((Mechanic::Silence, 3));
+        assert!(side.can_cast(0) && !side.can_withdraw(0));
+        side.conditions = vec![(Mechanic::Stun, 3)];
+        assert!(!side.can_cast(0) && !side.can_withdraw(0));
+    }
+
+    fn starter_sides(rng: &mut StdRng) -> (Side, Side) {
+        let config = Config::default();
+        let deck = DeckInputs::new(DeckType::Starter);
+        let own = Side::new(deck.build(&config, rng).unwrap(), &config.simulation, rng);
+        let opponent = Side::new(deck.build(&config, rng).unwrap(), &config.simulation, rng);
+        (own, opponent)
+    }
+
+    fn resolve(own: &mut Side, opponent: &mut Side, effect: Effect, mechanic: Mechanic, rng: &mut StdRng) {
+        resolve_effect(own, opponent, &effect, mechanic, Reach { count: 1, pattern: Pattern::Favoured }, 1, &MatchRules::default(), rng);
+    }
+
+    #[test]
+    fn barnacle_effects_move_barnacles_between_sides() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut own, mut opponent) = starter_sides(&mut rng);
+        (own.barnacles, opponent.barnacles) = (2, 2);
+        resolve(&mut own, &mut opponent, Effect::new("GainBarnacles").with_magnitude(3), Mechanic::GainBarnacles, &mut rng);
+        resolve(&mut own, &mut opponent, Effect::new("DrainBarnacles").with_magnitude(5), Mechanic::DrainBarnacles, &mut rng);
+        assert_eq!((own.barnacles, opponent.barnacles), (5, 0));
+        resolve(&mut own, &mut opponent, Effect::new("Discount").with_magnitude(2), Mechanic::Discount, &mut rng);
+        let card = own.hand[0].clone();
+        assert_eq!(own.cast_cost(&card), (card.barnacles - 2).max(0));
+    }
+
+    #[test]
+    fn card_flow_effects_move_cards_between_piles() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut own, mut opponent) = starter_sides(&mut rng);
+        let (hand, opponent_hand) = (own.hand.len(), opponent.hand.len());
+        resolve(&mut own, &mut opponent, Effect::new("Draw").with_magnitude(1), Mechanic::Draw, &mut rng);
+        resolve(&mut own, &mut opponent, Effect::new("Discard").with_magnitude(1), Mechanic::Discard, &mut rng);
+        assert_eq!((own.hand.len(), opponent.hand.len(), opponent.discard.len()), (hand + 1, opponent_hand - 1, 1));
+
+        own.discard = own.hand.drain(..2).collect();
+        let most_expensive = own.discard.iter().map(|card| card.barnacles).max().unwrap();
+        resolve(&mut own, &mut opponent, Effect::new("ReturnToHand").with_magnitude(1), Mechanic::ReturnToHand, &mut rng);
+        assert_eq!((own.discard.len(), own.hand.last().unwrap().barnacles), (1, most_expensive));
+
+        let rarity = own.draw_pile[0].rarity.clone();
+        let search = Effect::new("Search").with_magnitude(1).with_rarity(rarity.clone());
+        resolve(&mut own, &mut opponent, search, Mechanic::Search, &mut rng);
+        assert_eq!(own.hand.last().unwrap().rarity, rarity);
+    }
+
+    #[test]
+    fn priority_conditions_compare_against_the_opposing_cast() {
+        let config = Config::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut input = CardInput::new(Rarity::Legendary);
+        let gain = Outcome::Extra { effect: Effect::new("GainBarnacles") };
+        input.conditionals = vec![ConditionalEffect { condition: Condition::PriorityAbove { priority: 5 }, outcome: gain }];
+        let card = input.build(&config, &mut rng).unwrap();
+        let (own, opponent) = starter_sides(&mut rng);
+        let barnacles = own.barnacles;
+        for (opposing_priority, fired) in [(None, false), (Some(5), false), (Some(6), true)] {
+            let mut sides = [own.clone(), opponent.clone()];
+            resolve_card(&mut sides, 0, &card, opposing_priority, 1, &MatchRules::default(), &mut rng);
+            assert_eq!(sides[0].barnacles > barnacles, fired, "{:?}", opposing_priority);
+        }
+        let highest = config.priority.highest_priority();
+        input.conditionals[0].condition = Condition::PriorityAbove { priority: highest - 1 };
+        assert!(input.build(&config, &mut rng).is_ok());
+        input.conditionals[0].condition = Condition::PriorityAbove { priority: highest };
+        assert!(input.build(&config, &mut rng).unwrap_err().ends_with("can never trigger"));
+    }
+
+    #[test]
+    fn shaped_ranges_pick_members_by_position() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (own, mut opponent) = starter_sides(&mut rng);
+        opponent.members[2].health -= 5;
+        let rules = MatchRules::default();
+        let mut hit = |targets: Targets| {
+            let (mut own_after, mut opponent_after) = (own.clone(), opponent.clone());
+            let damage = Effect::new("Damage").with_magnitude(1);
+            resolve_effect(&mut own_after, &mut opponent_after, &damage, Mechanic::Damage, rules.reach(&targets), 1, &rules, &mut rng);
+            let hurt = |before: &Side, after: &Side| (0..before.members.len()).filter(|index| after.members[*index].health < before.members[*index].health).collect::<Vec<_>>();
+            (hurt(&own, &own_after), hurt(&opponent, &opponent_after))
+        };
+        assert_eq!(hit(Targets::Line { count: 2 }), (vec![], vec![0, 1]));
+        assert_eq!(hit(Targets::Cone { spread: 1 }), (vec![], vec![1, 2, 3]));
+        assert_eq!(hit(Targets::Cone { spread: 0 }), (vec![], vec![2]));
+        assert_eq!(hit(Targets::Caster), (vec![0], vec![]));
+        assert_eq!(hit(Targets::Allies), ((0..own.members.len()).collect(), vec![]));
+    }
+
+    #[test]
+    fn caster_ranges_turn_every_offensive_effect_on_the_caster() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut own, mut opponent) = starter_sides(&mut rng);
+        for side in [&mut own, &mut opponent] {
+            (side.barnacles, side.members[0].shield) = (2, 3);
+        }
+        let config = Config::default();
+        let rules = MatchRules::default();
+        let state = |side: &Side| {
+            let members: Vec<(i32, i32)> = side.members.iter().map(|member| (member.health, member.shield)).collect();
+            (members, side.barnacles, side.hand.len(), side.conditions.len())
+        };
+        for definition in config.effect_catalog.iter().filter(|definition| definition.mechanic.is_offensive()) {
+            let (mut own_after, mut opponent_after) = (own.clone(), opponent.clone());
+            let effect = Effect::new(&definition.id).with_magnitude(1);
+            resolve_effect(&mut own_after, &mut opponent_after, &effect, definition.mechanic, rules.reach(&Targets::Caster), 1, &rules, &mut rng);
+            assert_eq!(state(&opponent_after), state(&opponent), "{}", definition.id);
+            assert_ne!(state(&own_after), state(&own), "{}", definition.id);
+        }
+    }
+}
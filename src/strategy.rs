@@ -0,0 +1,111 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::{card::*, simulator::*};
+
+// Decides which action a side takes each turn, implement this to pit custom bots against decks
+pub trait Strategy {
+    fn name(&self) -> &str;
+    fn choose(&mut self, view: &TurnView, rng: &mut StdRng) -> Action;
+}
+
+pub fn builtin_strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(RandomStrategy),
+        Box::new(GreedyDamage),
+        Box::new(BarnacleHoarder),
+        Box::new(HealerFirst),
+    ]
+}
+
+fn is_damage(card: &Card) -> bool {
+    card.effects.first().and_then(|effect| card.config.mechanic(effect)).is_some_and(|mechanic| mechanic.is_damage())
+}
+
+fn best_by<F>(indices: Vec<usize>, cards: &[Card], value: F) -> Option<usize>
+where
+    F: Fn(&Card) -> i32,
+{
+    indices.into_iter().max_by_key(|index| value(&cards[*index]))
+}
+
+// Picks uniformly between every legal action, including passing
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &str {
+        "Random"
+    }
+
+    fn choose(&mut self, view: &TurnView, rng: &mut StdRng) -> Action {
+        let mut options: Vec<Action> = view.castable().into_iter().map(Action::Cast)
+            .chain(view.withdrawable().into_iter().map(Action::Withdraw))
+            .collect();
+        options.push(Action::Pass);
+        *options.choose(rng).unwrap_or(&Action::Pass)
+    }
+}
+
+// Casts whatever deals the most damage right now, recovering spent damage cards when nothing else is affordable
+pub struct GreedyDamage;
+
+impl Strategy for GreedyDamage {
+    fn name(&self) -> &str {
+        "Greedy Damage"
+    }
+
+    fn choose(&mut self, view: &TurnView, _rng: &mut StdRng) -> Action {
+        let hand = &view.own.hand;
+        let damage: Vec<usize> = view.castable().into_iter().filter(|index| is_damage(&hand[*index])).collect();
+        if let Some(index) = best_by(damage, hand, |card| effect_value(card, view.rules)) {
+            return Action::Cast(index);
+        }
+        let discard = &view.own.discard;
+        let withdraw: Vec<usize> = view.withdrawable().into_iter().filter(|index| is_damage(&discard[*index])).collect();
+        if let Some(index) = best_by(withdraw, discard, |card| effect_value(card, view.rules)) {
+            return Action::Withdraw(index);
+        }
+        best_by(view.castable(), hand, |card| card.barnacles).map_or(Action::Pass, Action::Cast)
+    }
+}
+
+// Saves up until the most expensive card in hand is affordable, then casts it
+pub struct BarnacleHoarder;
+
+impl Strategy for BarnacleHoarder {
+    fn name(&self) -> &str {
+        "Barnacle Hoarder"
+    }
+
+    fn choose(&mut self, view: &TurnView, _rng: &mut StdRng) -> Action {
+        let hand = &view.own.hand;
+        if hand.is_empty() {
+            let discard = &view.own.discard;
+            return best_by(view.withdrawable(), discard, |card| card.barnacles).map_or(Action::Pass, Action::Withdraw);
+        }
+        let most_expensive = best_by((0..hand.len()).collect(), hand, |card| card.barnacles);
+        match most_expensive {
+            Some(index) if view.own.can_cast(index) => Action::Cast(index),
+            _ => Action::Pass,
+        }
+    }
+}
+
+// Patches up the party before attacking
+pub struct HealerFirst;
+
+impl Strategy for HealerFirst {
+    fn name(&self) -> &str {
+        "Healer First"
+    }
+
+    fn choose(&mut self, view: &TurnView, _rng: &mut StdRng) -> Action {
+        let hand = &view.own.hand;
+        let (damage, support): (Vec<usize>, Vec<usize>) = view.castable().into_iter().partition(|index| is_damage(&hand[*index]));
+        if view.own.missing_health(view.rules) > 0 {
+            if let Some(index) = best_by(support, hand, |card| effect_value(card, view.rules)) {
+                return Action::Cast(index);
+            }
+        }
+        best_by(damage, hand, |card| effect_value(card, view.rules)).map_or(Action::Pass, Action::Cast)
+    }
+}
@@ -0,0 +1,158 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, catalog::*, locale::*};
+
+// Rules text for an effect, a template naming the card's range is preferred over one without a range
+// (not `effect`/`range`, those keys hold shorthand enums and are expanded when a document is read)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RulesTemplate {
+    pub effect_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_id: Option<String>,
+    pub text: String,
+}
+
+// Every {placeholder} a template may use
+pub const PLACEHOLDERS: [&str; 10] = ["magnitude", "total", "s", "duration", "turns", "tick", "every", "targets", "range", "rarity"];
+
+fn template(effect: &str, range: Option<&str>, text: &str) -> RulesTemplate {
+    RulesTemplate { effect_id: String::from(effect), range_id: range.map(String::from), text: String::from(text) }
+}
+
+pub fn builtin_templates() -> Vec<RulesTemplate> {
+    vec![
+        template("Damage", None, "Deal {magnitude} damage to {targets}"),
+        template("Damage", Some("AoE"), "Deal {magnitude} damage to all enemies in the room"),
+        template("Damage", Some("ExtendedAoE"), "Deal {magnitude} damage to the whole enemy party"),
+        template("Damage", Some("Random"), "Deal {magnitude} damage to each of {targets}"),
+        template("Damage", Some("Line"), "Deal {magnitude} damage to {targets}, front first"),
+        template("Damage", Some("Cone"), "Deal {magnitude} damage to the weakest enemy and the enemies beside it"),
+        template("Heal", None, "Heal {targets} for {magnitude}"),
+        template("Heal", Some("AoE"), "Heal all allies in the room for {magnitude}"),
+        template("Heal", Some("ExtendedAoE"), "Heal your whole party for {magnitude}"),
+        template("Heal", Some("Self"), "Heal yourself for {magnitude}"),
+        template("Heal", Some("Allies"), "Heal every ally for {magnitude}"),
+        template("AcidHeal", None, "Heal {targets} for {magnitude} and strip {magnitude} shield from as many enemies"),
+        template("Shield", None, "Shield {targets} for {magnitude}"),
+        template("Shield", Some("Self"), "Shield yourself for {magnitude}"),
+        template("Shield", Some("Allies"), "Shield every ally for {magnitude}"),
+        template("Poison", None, "Deal {magnitude} damage to {targets} {every} for {turns}, ignoring shields"),
+        template("Regeneration", None, "Heal {targets} for {magnitude} {every} for {turns}"),
+        template("TimedShield", None, "Shield {targets} for {magnitude} for {turns}"),
+        template("Stun", None, "Stun the enemy for {turns}"),
+        template("Slow", None, "Slow the enemy for {turns}"),
+        template("Taunt", None, "Force the enemy to target you for {turns}"),
+        template("Silence", None, "Silence the enemy for {turns}"),
+        template("GainBarnacles", None, "Gain {magnitude} barnacle{s}"),
+        template("DrainBarnacles", None, "The enemy loses {magnitude} barnacle{s}"),
+        template("Discount", None, "Your next card costs {magnitude} barnacle{s} less"),
+        template("Draw", None, "Draw {magnitude} card{s}"),
+        template("Discard", None, "The enemy discards {magnitude} random card{s}"),
+        template("ReturnToHand", None, "Return your {magnitude} most expensive discarded card{s} to your hand"),
+        template("Search", None, "Search your draw pile for {magnitude} {rarity} card{s}"),
+    ]
+}
+
+fn turns(count: i32) -> String {
+    if count == 1 { tr("rules-turn", &[]) } else { tr("rules-turns", &[("count", &count)]) }
+}
+
+fn targets_phrase(targets: &Targets) -> String {
+    match targets {
+        Targets::Count { count: 1 } => tr("rules-one-target", &[]),
+        Targets::Count { count } => tr("rules-targets", &[("count", count)]),
+        Targets::Random { count: 1 } => tr("rules-one-random-target", &[]),
+        Targets::Random { count } => tr("rules-random-targets", &[("count", count)]),
+        Targets::Room => tr("rules-room", &[]),
+        Targets::Party => tr("rules-party", &[]),
+        Targets::Allies => tr("rules-allies", &[]),
+        Targets::Caster => tr("rules-caster", &[]),
+        Targets::Line { count } => tr("rules-line", &[("count", count)]),
+        Targets::Cone { spread: 0 } => tr("rules-one-target", &[]),
+        Targets::Cone { spread } => tr("rules-cone", &[("spread", spread)]),
+    }
+}
+
+impl Config {
+    pub fn rules_template(&self, effect: &Effect, range: &Range) -> Option<&RulesTemplate> {
+        let for_effect = || self.rules_templates.iter().filter(|template| same_id(&template.effect_id, &effect.id));
+        for_effect()
+            .find(|template| template.range_id.as_ref().is_some_and(|id| same_id(id, &range.id)))
+            .or_else(|| for_effect().find(|template| template.range_id.is_none()))
+    }
+
+    // Fills the effect's template, effects without one are described as they are elsewhere
+    pub fn rules_text(&self, effect: &Effect, range: &Range) -> String {
+        let Some(template) = self.rules_template(effect, range) else {
+            return self.describe_effect(effect);
+        };
+        let tick = effect.tick.unwrap_or(default_tick());
+        let values = [
+            ("magnitude", effect.magnitude.to_string()),
+            ("total", effect.total_magnitude().to_string()),
+            ("s", String::from(if effect.magnitude == 1 { "" } else { "s" })),
+            ("duration", effect.duration.unwrap_or(default_duration()).to_string()),
+            ("turns", turns(effect.duration.unwrap_or(default_duration()))),
+            ("tick", tick.to_string()),
+            ("every", if tick == 1 { tr("rules-every-turn", &[]) } else { tr("rules-every", &[("count", &tick)]) }),
+            ("targets", self.range_definition(range).map_or(range.id.clone(), |definition| targets_phrase(&definition.targets))),
+            ("range", self.describe_range(range)),
+            ("rarity", effect.rarity.clone().unwrap_or(default_search_rarity()).label()),
+        ];
+        values.iter().fold(tr_or(&rules_key(template), &template.text, &[]), |text, (key, value)| text.replace(&format!("{{{}}}", key), value))
+    }
+
+    pub fn card_rules_text(&self, effects: &[Effect], range: &Range) -> String {
+        effects.iter().map(|effect| format!("{}.", self.rules_text(effect, range))).collect::<Vec<String>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn range_specific_templates_come_first() {
+        let config = Config::default();
+        let damage = Effect::new("Damage").with_magnitude(3);
+        assert_eq!(config.rules_text(&damage, &Range::new("AoE")), "Deal 3 damage to all enemies in the room");
+        assert_eq!(config.rules_text(&damage, &Range::new("Multiple")), "Deal 3 damage to 2 targets");
+        let poison = Effect::new("Poison").with_magnitude(1).with_timing(4, 2);
+        assert_eq!(config.rules_text(&poison, &Range::new("Single")), "Deal 1 damage to one target every 2 turns for 4 turns, ignoring shields");
+        let draw = [Effect::new("Draw").with_magnitude(1), Effect::new("Discard").with_magnitude(2)];
+        assert_eq!(config.card_rules_text(&draw, &Range::new("Single")), "Draw 1 card. The enemy discards 2 random cards.");
+    }
+
+    #[test]
+    fn effects_without_a_template_are_described() {
+        let mut config = Config::default();
+        config.rules_templates.retain(|template| template.effect_id != "Shield");
+        let shield = Effect::new("Shield").with_magnitude(4);
+        assert_eq!(config.rules_text(&shield, &Range::new("Single")), config.describe_effect(&shield));
+    }
+
+    #[test]
+    fn blank_flavour_is_dropped() {
+        let mut input = CardInput::new(Rarity::Rare);
+        input.flavour = Some(String::from("  "));
+        let build = |input: &CardInput| input.build(&Config::default(), &mut StdRng::seed_from_u64(0)).unwrap().flavour;
+        assert_eq!(build(&input), None);
+        input.flavour = Some(String::from("Smells of brine"));
+        assert_eq!(build(&input).as_deref(), Some("Smells of brine"));
+    }
+
+    #[test]
+    fn shaped_ranges_name_their_targets() {
+        let config = Config::default();
+        let heal = Effect::new("Heal").with_magnitude(2);
+        assert_eq!(config.rules_text(&Effect::new("Regeneration").with_magnitude(2), &Range::new("Self")), "Heal the caster for 2 every turn for 3 turns");
+        assert_eq!(config.rules_text(&heal, &Range::new("Self")), "Heal yourself for 2");
+        assert_eq!(config.rules_text(&heal, &Range::new("Allies")), "Heal every ally for 2");
+        assert_eq!(config.rules_text(&heal, &Range::new("Random")), "Heal 3 random targets for 2");
+        assert_eq!(config.rules_text(&heal, &Range::new("Line")), "Heal 3 targets in a line for 2");
+        assert_eq!(config.rules_text(&heal, &Range::new("Cone")), "Heal one target and up to 1 on either side of it for 2");
+    }
+}
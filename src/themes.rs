@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use rand::{distributions::{Distribution, WeightedIndex}, Rng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{card::*, catalog::*, names::*, retier::*};
+
+// Relative chance of picking each id, ids left out are never picked and an empty table picks every id evenly
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct Weights(pub BTreeMap<String, f32>);
+
+impl Weights {
+    pub fn get(&self, id: &str) -> Option<f32> {
+        self.0.iter().find(|(key, _)| same_id(key, id)).map(|(_, weight)| *weight)
+    }
+
+    pub fn pick<R: Rng + ?Sized>(&self, ids: &[String], rng: &mut R) -> Option<String> {
+        let weighted: Vec<(&String, f32)> = ids
+            .iter()
+            .filter_map(|id| if self.0.is_empty() { Some((id, 1.0)) } else { Some((id, self.get(id)?)) })
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        let index = WeightedIndex::new(weighted.iter().map(|(_, weight)| *weight)).ok()?.sample(rng);
+        Some(weighted[index].0.clone())
+    }
+}
+
+// Weights random decks pick their cards with
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Theme {
+    pub id: String,
+    #[serde(default)]
+    pub effects: Weights,
+    #[serde(default)]
+    pub ranges: Weights,
+    #[serde(default)]
+    pub efficiency: Weights,
+    // Extra words for the names of the theme's cards, see `NameLists`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub adjectives: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nouns: Vec<String>,
+}
+
+fn weights(entries: &[(&str, f32)]) -> Weights {
+    Weights(entries.iter().map(|(id, weight)| (String::from(*id), *weight)).collect())
+}
+
+fn words(words: &[&str]) -> Vec<String> {
+    words.iter().map(|word| String::from(*word)).collect()
+}
+
+pub fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme {
+            id: String::from("Balanced"),
+            effects: Weights::default(),
+            ranges: Weights::default(),
+            efficiency: Weights::default(),
+            adjectives: words(&["Steady", "Tidal", "Salted", "Drifting", "Coral"]),
+            nouns: words(&["Current", "Tide", "Reef", "Wake", "Shoal"]),
+        },
+        Theme {
+            id: String::from("Healer"),
+            effects: weights(&[("Heal", 4.0), ("Regeneration", 3.0), ("AcidHeal", 2.0), ("Shield", 2.0), ("TimedShield", 1.0), ("Damage", 1.0)]),
+            ranges: weights(&[("Single", 2.0), ("Multiple", 2.0), ("AoE", 1.0), ("ExtendedAoE", 1.0)]),
+            efficiency: weights(&[("Normal", 2.0), ("Good", 1.0)]),
+            adjectives: words(&["Soothing", "Gentle", "Mending", "Warm", "Kelp"]),
+            nouns: words(&["Balm", "Spring", "Embrace", "Blessing", "Shelter"]),
+        },
+        Theme {
+            id: String::from("Striker"),
+            effects: weights(&[("Damage", 4.0), ("Poison", 3.0), ("DrainBarnacles", 1.0), ("Stun", 1.0)]),
+            ranges: weights(&[("Single", 3.0), ("Multiple", 2.0), ("AoE", 1.0)]),
+            efficiency: weights(&[("Normal", 2.0), ("Good", 1.0), ("Bad", 1.0)]),
+            adjectives: words(&["Jagged", "Venomous", "Crashing", "Barbed", "Sunken"]),
+            nouns: words(&["Harpoon", "Barrage", "Fang", "Breaker", "Sting"]),
+        },
+        Theme {
+            id: String::from("Controller"),
+            effects: weights(&[("Stun", 2.0), ("Slow", 3.0), ("Taunt", 2.0), ("Silence", 2.0), ("Damage", 1.0)]),
+            ranges: weights(&[("Single", 3.0), ("Multiple", 1.0)]),
+            efficiency: weights(&[("Normal", 1.0), ("Good", 1.0)]),
+            adjectives: words(&["Binding", "Murky", "Still", "Heavy", "Silent"]),
+            nouns: words(&["Net", "Undertow", "Anchor", "Fog", "Depths"]),
+        },
+        Theme {
+            id: String::from("Trickster"),
+            effects: weights(&[("Draw", 2.0), ("Discard", 2.0), ("ReturnToHand", 2.0), ("Search", 1.0), ("GainBarnacles", 1.0), ("Discount", 1.0)]),
+            ranges: weights(&[("Single", 1.0)]),
+            efficiency: Weights::default(),
+            adjectives: words(&["Sly", "Shimmering", "Hidden", "Lucky", "Pilfered"]),
+            nouns: words(&["Pearl", "Trinket", "Mirage", "Bargain", "Secret"]),
+        },
+    ]
+}
+
+impl Config {
+    pub fn theme(&self, id: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| same_id(&theme.id, id))
+    }
+}
+
+impl Theme {
+    fn random_card<R: Rng + ?Sized>(&self, rarity: Rarity, config: &Config, rng: &mut R) -> Option<CardInput> {
+        let effects: Vec<String> = config.effect_catalog.iter().map(|definition| definition.id.clone()).collect();
+        let ranges: Vec<String> = config.range_catalog.iter().map(|definition| definition.id.clone()).collect();
+        let tiers: Vec<String> = config.efficiency.iter().map(|tier| tier.id.clone()).collect();
+        let power = config.rarity_ranges.get(&rarity);
+        let mut input = CardInput::new(rarity);
+        input.efficiency = Efficiency::new(&self.efficiency.pick(&tiers, rng)?);
+        input.range = Range::new(&self.ranges.pick(&ranges, rng)?);
+        input.effects = vec![Effect::new(&self.effects.pick(&effects, rng)?)];
+        input.priority_allocation = rng.gen_range(1..power.min.max(2));
+        Some(input)
+    }
+}
+
+// Cards whose effects were priced down to nothing build fine but do nothing when cast
+fn does_something(card: &Card, config: &Config) -> bool {
+    card.effects.iter().all(|effect| effect.magnitude > 0 || config.mechanic(effect).is_some_and(|mechanic| mechanic.kind() == EffectKind::Control))
+}
+
+// Attempts per card before the theme is judged unable to fill a slot
+const RANDOM_CARD_ATTEMPTS: u32 = 200;
+
+// Fills every card of the deck type with picks from the theme, keeping only cards that build and do something on either power roll
+pub fn random_deck<R: Rng + ?Sized>(deck_type: DeckType, theme: &Theme, config: &Config, rng: &mut R) -> Result<DeckInputs, String> {
+    let mut deck = DeckInputs::new(deck_type);
+    let mut names = NameGenerator::new(config).with_theme(theme);
+    for slot in deck.inputs.iter_mut() {
+        let power = config.rarity_ranges.get(&slot.rarity);
+        let card = (0..RANDOM_CARD_ATTEMPTS)
+            .filter_map(|_| theme.random_card(slot.rarity.clone(), config, rng))
+            .find(|input| [power.min, power.max].iter().all(|power| build_with_power(input, *power, config).is_ok_and(|card| does_something(&card, config))));
+        let Some(mut card) = card else {
+            return Err(format!("Could not find a {} card the {} theme can build", slot.rarity.label(), theme.id));
+        };
+        card.name = names.name(&card.rarity, Some(&card.range), &card.effects, rng);
+        *slot = card;
+    }
+    Ok(deck)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn weights_only_pick_listed_ids() {
+        let ids = [String::from("Damage"), String::from("Heal"), String::from("Stun")];
+        let mut rng = StdRng::seed_from_u64(0);
+        let picked = |weights: &Weights, rng: &mut StdRng| (0..50).filter_map(|_| weights.pick(&ids, rng)).collect::<Vec<String>>();
+        assert!(picked(&weights(&[("heal", 1.0), ("Stun", 0.0)]), &mut rng).iter().all(|id| id == "Heal"));
+        assert_eq!(picked(&Weights::default(), &mut rng).len(), 50);
+        assert_eq!(weights(&[("Stun", 0.0)]).pick(&ids, &mut rng), None);
+    }
+
+    #[test]
+    fn random_decks_follow_the_theme() {
+        let config = Config::default();
+        let theme = config.theme("healer").unwrap();
+        let deck = random_deck(DeckType::Legendary, theme, &config, &mut StdRng::seed_from_u64(5)).unwrap();
+        for input in deck.inputs.iter() {
+            assert!(theme.effects.get(&input.effects[0].id).is_some_and(|weight| weight > 0.0), "{}", input.effects[0].id);
+            assert!(theme.ranges.get(&input.range.id).is_some());
+            assert!(input.build(&config, &mut StdRng::seed_from_u64(0)).is_ok());
+        }
+        assert!(deck.validate(&config).is_valid());
+    }
+}
@@ -0,0 +1,240 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{card::*, catalog::*, simulator::*};
+
+#[derive(Debug, Clone)]
+pub enum Objective {
+    // Overall win rate of a deck against an opponent across every built-in strategy pairing
+    WinRate { deck_name: String, deck: Box<DeckInputs>, opponent_name: String, opponent: Box<DeckInputs>, target: f32 },
+    // Mean relative gap between heal per barnacle and damage per barnacle across every rarity and range
+    HealDamageParity { tolerance: f32 },
+}
+
+impl Objective {
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::WinRate { deck_name, opponent_name, target, .. } => format!("{} vs {} win rate (target {:.1}%)", deck_name, opponent_name, target * 100.0),
+            Objective::HealDamageParity { tolerance } => format!("Heal/damage per barnacle gap (within {:.1}%)", tolerance * 100.0),
+        }
+    }
+
+    pub fn measure(&self, config: &Config, options: &TuningOptions) -> Result<f32, String> {
+        match self {
+            Objective::WinRate { deck, opponent, .. } => {
+                let reports = round_robin([deck.as_ref(), opponent.as_ref()], config, options.games, options.seed)?;
+                Ok(reports.iter().map(|report| report.win_rate()).sum::<f32>() / reports.len().max(1) as f32)
+            }
+            Objective::HealDamageParity { .. } => Ok(heal_damage_gap(config, options.seed)),
+        }
+    }
+
+    pub fn loss(&self, measured: f32) -> f32 {
+        match self {
+            Objective::WinRate { target, .. } => (measured - target).abs(),
+            Objective::HealDamageParity { tolerance } => (measured - tolerance).max(0.0),
+        }
+    }
+}
+
+fn heal_damage_gap(config: &Config, seed: u64) -> f32 {
+    let rarities = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+    let ranges: Vec<Range> = config.range_catalog.iter().map(RangeDefinition::range).collect();
+    let mut gaps = Vec::new();
+    for rarity in rarities.iter() {
+        for range in ranges.iter() {
+            let per_barnacle = |effect: Effect| {
+                let mut input = CardInput::new(rarity.clone());
+                input.efficiency = Efficiency::new("Normal");
+                input.range = range.clone();
+                input.effects = vec![effect];
+                let card = input.build(config, &mut StdRng::seed_from_u64(seed)).ok()?;
+                Some(card.effects.first()?.magnitude() as f32 / card.barnacles.max(1) as f32)
+            };
+            if let (Some(damage), Some(heal)) = (per_barnacle(Effect::new("Damage")), per_barnacle(Effect::new("Heal"))) {
+                if damage > 0.0 {
+                    gaps.push((heal - damage).abs() / damage);
+                }
+            }
+        }
+    }
+    gaps.iter().sum::<f32>() / gaps.len().max(1) as f32
+}
+
+#[derive(Debug, Clone)]
+pub struct TuningOptions {
+    pub iterations: u32,
+    pub games: u32,
+    pub seed: u64,
+}
+
+impl Default for TuningOptions {
+    fn default() -> Self {
+        TuningOptions { iterations: 200, games: 10, seed: 0 }
+    }
+}
+
+pub struct TuningResult {
+    pub config: Config,
+    pub before: Vec<f32>,
+    pub after: Vec<f32>,
+    pub accepted: u32,
+}
+
+fn float_knobs(config: &mut Config) -> Vec<(String, &mut f32)> {
+    fn range_knobs<'a>(prefix: &str, modifiers: &'a mut RangeModifiers, knobs: &mut Vec<(String, &'a mut f32)>) {
+        for (range, modifier) in modifiers.0.iter_mut() {
+            knobs.push((format!("{}.{}", prefix, range), modifier));
+        }
+    }
+    let priority = &mut config.power_to_priority;
+    let mut knobs = vec![
+        (String::from("power_to_priority.common"), &mut priority.common),
+        (String::from("power_to_priority.uncommon"), &mut priority.uncommon),
+        (String::from("power_to_priority.rare"), &mut priority.rare),
+        (String::from("power_to_priority.epic"), &mut priority.epic),
+        (String::from("power_to_priority.legendary"), &mut priority.legendary),
+    ];
+    // Control effects have a fixed price per turn, only magnitude pricing is tuned
+    for definition in config.effect_catalog.iter_mut().filter(|definition| definition.mechanic.kind() != EffectKind::Control) {
+        range_knobs(&format!("effect_catalog.{}.range_modifiers", definition.id), &mut definition.range_modifiers, &mut knobs);
+    }
+    for tier in config.efficiency.iter_mut() {
+        knobs.push((format!("efficiency.{}.multiplier", tier.id), &mut tier.multiplier));
+    }
+    knobs.push((String::from("barnacle_pricing.range_multiplier"), &mut config.barnacle_pricing.range_multiplier));
+    let conditions = &mut config.condition_costs;
+    knobs.push((String::from("condition_costs.priority_above"), &mut conditions.priority_above));
+    knobs.push((String::from("condition_costs.target_shielded"), &mut conditions.target_shielded));
+    knobs.push((String::from("condition_costs.on_withdraw"), &mut conditions.on_withdraw));
+    knobs
+}
+
+fn power_knobs(config: &mut Config) -> Vec<(String, &mut PowerRange)> {
+    let ranges = &mut config.rarity_ranges;
+    vec![
+        (String::from("rarity_ranges.common"), &mut ranges.common),
+        (String::from("rarity_ranges.uncommon"), &mut ranges.uncommon),
+        (String::from("rarity_ranges.rare"), &mut ranges.rare),
+        (String::from("rarity_ranges.epic"), &mut ranges.epic),
+        (String::from("rarity_ranges.legendary"), &mut ranges.legendary),
+    ]
+}
+
+// Nudges a single tunable value, keeping multipliers positive and power ranges ordered
+fn mutate(config: &Config, rng: &mut StdRng) -> Config {
+    let mut candidate = config.clone();
+    if rng.gen_bool(0.75) {
+        let mut knobs = float_knobs(&mut candidate);
+        if let Some((_, value)) = knobs.choose_mut(rng) {
+            let step = rng.gen_range(0.85..1.15);
+            **value = ((**value * step * 8.0).round() / 8.0).max(0.125);
+        }
+    } else {
+        let mut knobs = power_knobs(&mut candidate);
+        if let Some((_, range)) = knobs.choose_mut(rng) {
+            let step = if rng.gen_bool(0.5) { 1 } else { -1 };
+            if rng.gen_bool(0.5) {
+                range.min = (range.min + step).clamp(1, range.max);
+            } else {
+                range.max = (range.max + step).max(range.min);
+            }
+        }
+    }
+    candidate
+}
+
+fn score(objectives: &[Objective], config: &Config, options: &TuningOptions) -> Result<(f32, Vec<f32>), String> {
+    let mut total = 0.0;
+    let mut measured = Vec::new();
+    for objective in objectives.iter() {
+        let value = objective.measure(config, options)?;
+        total += objective.loss(value);
+        measured.push(value);
+    }
+    Ok((total, measured))
+}
+
+// Seeded hill climbing: every candidate is scored with the same seed so only the config changes between runs
+pub fn tune(config: &Config, objectives: &[Objective], options: &TuningOptions) -> Result<TuningResult, String> {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let (mut best_score, before) = score(objectives, config, options)?;
+    let mut best = config.clone();
+    let mut best_measured = before.clone();
+    let mut accepted = 0;
+    for _ in 0..options.iterations {
+        let candidate = mutate(&best, &mut rng);
+        if !candidate.validate().is_valid() {
+            continue;
+        }
+        let Ok((candidate_score, measured)) = score(objectives, &candidate, options) else {
+            continue;
+        };
+        if candidate_score < best_score {
+            best = candidate;
+            best_score = candidate_score;
+            best_measured = measured;
+            accepted += 1;
+        }
+    }
+    Ok(TuningResult { config: best, before, after: best_measured, accepted })
+}
+
+pub fn config_changes(before: &Config, after: &Config) -> Vec<String> {
+    let (mut before, mut after) = (before.clone(), after.clone());
+    let mut changes = Vec::new();
+    for ((name, old), (_, new)) in float_knobs(&mut before).into_iter().zip(float_knobs(&mut after)) {
+        if old != new {
+            changes.push(format!("{}: {} -> {}", name, old, new));
+        }
+    }
+    for ((name, old), (_, new)) in power_knobs(&mut before).into_iter().zip(power_knobs(&mut after)) {
+        if old.min != new.min || old.max != new.max {
+            changes.push(format!("{}: {}..{} -> {}..{}", name, old.min, old.max, new.min, new.max));
+        }
+    }
+    changes
+}
+
+pub fn tuning_report(original: &Config, objectives: &[Objective], options: &TuningOptions, result: &TuningResult) -> String {
+    let mut report = format!(
+        "Tuning report ({} iterations, {} games per matchup, seed {})\n{} improvements accepted\n\nObjectives:\n",
+        options.iterations, options.games, options.seed, result.accepted
+    );
+    for (index, objective) in objectives.iter().enumerate() {
+        report.push_str(&format!("\t{}: {:.3} -> {:.3}\n", objective.describe(), result.before[index], result.after[index]));
+    }
+    report.push_str("\nChanges:\n");
+    let changes = config_changes(original, &result.config);
+    if changes.is_empty() {
+        report.push_str("\tNone\n");
+    }
+    for change in changes {
+        report.push_str(&format!("\t{}\n", change));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuning_never_makes_the_objectives_worse() {
+        let config = Config::default();
+        let objectives = [Objective::HealDamageParity { tolerance: 0.0 }];
+        let options = TuningOptions { iterations: 30, games: 1, seed: 3 };
+        let result = tune(&config, &objectives, &options).unwrap();
+        assert!(objectives[0].loss(result.after[0]) <= objectives[0].loss(result.before[0]));
+        assert!(result.config.validate().is_valid());
+        assert_eq!(config_changes(&config, &result.config).is_empty(), result.accepted == 0);
+        let again = tune(&config, &objectives, &options).unwrap();
+        assert_eq!(config_changes(&result.config, &again.config), Vec::<String>::new());
+    }
+
+    #[test]
+    fn losses_measure_distance_from_the_target() {
+        let parity = Objective::HealDamageParity { tolerance: 0.1 };
+        assert_eq!(parity.loss(0.05), 0.0);
+        assert!((parity.loss(0.25) - 0.15).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,402 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{card::*, catalog::*, priority::*, simulator::*, templates::*, themes::*};
+
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn error(&mut self, message: String) {
+        self.errors.push(message);
+    }
+
+    pub fn warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    pub fn print(&self) {
+        for error in self.errors.iter() {
+            eprintln!("ERROR: {}", error);
+        }
+        for warning in self.warnings.iter() {
+            println!("WARNING: {}", warning);
+        }
+    }
+}
+
+fn check_multiplier(report: &mut ValidationReport, name: String, value: f32) {
+    if !value.is_finite() || value <= 0.0 {
+        report.error(format!("{} must be a positive number (found {})", name, value));
+    } else if !(0.25..=4.0).contains(&value) {
+        report.warning(format!("{} is {}, effects will be priced far outside the usual 0.25..4 range", name, value));
+    }
+}
+
+fn check_range_modifiers(report: &mut ValidationReport, name: &str, modifiers: &RangeModifiers, config: &Config) {
+    for (range, modifier) in modifiers.0.iter() {
+        check_multiplier(report, format!("{}.{}", name, range), *modifier);
+        if !config.range_catalog.iter().any(|definition| definition.matches(range)) {
+            report.warning(format!("{}.{} is not in range_catalog and is never used", name, range));
+        }
+    }
+}
+
+fn check_range_catalog(report: &mut ValidationReport, config: &Config) {
+    if config.range_catalog.is_empty() {
+        report.error(String::from("range_catalog is empty, cards have no ranges to choose from"));
+    }
+    for (index, definition) in config.range_catalog.iter().enumerate() {
+        let name = format!("range_catalog.{}", definition.id);
+        if config.range_catalog[..index].iter().any(|other| other.matches(&definition.id)) {
+            report.error(format!("{}: more than one range uses this id", name));
+        }
+        if definition.cost < 0 {
+            report.error(format!("{}.cost cannot be negative (found {})", name, definition.cost));
+        }
+        check_multiplier(report, format!("{}.modifier", name), definition.modifier);
+        if let Targets::Count { count } | Targets::Random { count } | Targets::Line { count } = definition.targets {
+            if count == 0 {
+                report.error(format!("{}.targets must reach at least 1 member", name));
+            } else if count > config.simulation.party_size {
+                report.warning(format!("{}.targets reaches {} members but simulation.party_size is {}", name, count, config.simulation.party_size));
+            }
+        }
+    }
+}
+
+fn check_effect_catalog(report: &mut ValidationReport, config: &Config) {
+    if config.effect_catalog.is_empty() {
+        report.error(String::from("effect_catalog is empty, cards have no effects to choose from"));
+    }
+    for (index, definition) in config.effect_catalog.iter().enumerate() {
+        let name = format!("effect_catalog.{}", definition.id);
+        if config.effect_catalog[..index].iter().any(|other| other.matches(&definition.id)) {
+            report.error(format!("{}: more than one effect uses this id", name));
+        }
+        let modifiers = &definition.range_modifiers;
+        if definition.mechanic.kind() == EffectKind::Control {
+            // Control effects are priced per turn so their modifiers sit well above the usual range
+            for (range, &modifier) in modifiers.0.iter() {
+                if !modifier.is_finite() || modifier <= 0.0 {
+                    report.error(format!("{}.range_modifiers.{} must be a positive number (found {})", name, range, modifier));
+                }
+            }
+        } else {
+            check_range_modifiers(report, &format!("{}.range_modifiers", name), modifiers, config);
+        }
+        check_multiplier(report, format!("{}.barnacle_multiplier", name), definition.barnacle_multiplier);
+        if definition.mechanic == Mechanic::GainBarnacles && definition.barnacle_multiplier <= 1.0 {
+            report.warning(format!("{}.barnacle_multiplier is {}, gaining barnacles costs no more than the barnacles gained", name, definition.barnacle_multiplier));
+        }
+    }
+    if config.min_control_barnacles < 1 {
+        report.warning(format!("min_control_barnacles is {}, control cards can be free to cast", config.min_control_barnacles));
+    }
+}
+
+fn check_barnacle_pricing(report: &mut ValidationReport, config: &Config) {
+    if config.efficiency.is_empty() {
+        report.error(String::from("efficiency is empty, cards have no efficiency to choose from"));
+    }
+    for (index, tier) in config.efficiency.iter().enumerate() {
+        if config.efficiency[..index].iter().any(|other| other.matches(&tier.id)) {
+            report.error(format!("efficiency.{}: more than one tier uses this id", tier.id));
+        }
+        check_multiplier(report, format!("efficiency.{}.multiplier", tier.id), tier.multiplier);
+    }
+    let pricing = &config.barnacle_pricing;
+    check_multiplier(report, String::from("barnacle_pricing.effect_multiplier"), pricing.effect_multiplier);
+    check_multiplier(report, String::from("barnacle_pricing.range_multiplier"), pricing.range_multiplier);
+    if !pricing.withdraw_fraction.is_finite() || pricing.withdraw_fraction <= 0.0 || pricing.withdraw_fraction > 1.0 {
+        report.error(format!("barnacle_pricing.withdraw_fraction must be above 0 and at most 1 (found {})", pricing.withdraw_fraction));
+    }
+    if pricing.min_withdraw < 0 {
+        report.error(format!("barnacle_pricing.min_withdraw cannot be negative (found {})", pricing.min_withdraw));
+    }
+}
+
+fn check_priority(report: &mut ValidationReport, model: &PriorityModel) {
+    if model.max_priority < 1 {
+        report.error(format!("priority.max_priority must be at least 1 (found {})", model.max_priority));
+    }
+    if model.step < 1 {
+        report.error(format!("priority.step must be at least 1 (found {})", model.step));
+    } else if model.offset < 0 || model.offset >= model.step {
+        report.error(format!("priority.offset must be between 0 and step - 1 (found {})", model.offset));
+    }
+    for (index, limit) in model.rarity_limits.iter().enumerate() {
+        if model.rarity_limits[..index].iter().any(|other| other.rarity == limit.rarity) {
+            report.warning(format!("priority.rarity_limits lists {:?} more than once, only the first is used", limit.rarity));
+        }
+        if limit.min > limit.max {
+            report.error(format!("priority.rarity_limits.{:?}: min ({}) is greater than max ({})", limit.rarity, limit.min, limit.max));
+        }
+        if limit.min < 0 || limit.max > model.max_priority {
+            report.warning(format!("priority.rarity_limits.{:?} reaches outside 0..{}", limit.rarity, model.max_priority));
+        }
+    }
+}
+
+fn check_weights(report: &mut ValidationReport, name: &str, weights: &Weights, ids: &[&str]) {
+    for (id, weight) in weights.0.iter() {
+        if !weight.is_finite() || *weight < 0.0 {
+            report.error(format!("{}.{} must be zero or a positive number (found {})", name, id, weight));
+        }
+        if !ids.iter().any(|known| same_id(known, id)) {
+            report.warning(format!("{}.{} is not in the config and is never picked", name, id));
+        }
+    }
+    if !weights.0.is_empty() && !ids.iter().any(|id| weights.get(id).is_some_and(|weight| weight > 0.0)) {
+        report.error(format!("{} gives no known id a positive weight, nothing can be picked", name));
+    }
+}
+
+fn check_themes(report: &mut ValidationReport, config: &Config) {
+    let effects: Vec<&str> = config.effect_catalog.iter().map(|definition| definition.id.as_str()).collect();
+    let ranges: Vec<&str> = config.range_catalog.iter().map(|definition| definition.id.as_str()).collect();
+    let tiers: Vec<&str> = config.efficiency.iter().map(|tier| tier.id.as_str()).collect();
+    for (index, theme) in config.themes.iter().enumerate() {
+        let name = format!("themes.{}", theme.id);
+        if config.themes[..index].iter().any(|other| same_id(&other.id, &theme.id)) {
+            report.error(format!("{}: more than one theme uses this id", name));
+        }
+        check_weights(report, &format!("{}.effects", name), &theme.effects, &effects);
+        check_weights(report, &format!("{}.ranges", name), &theme.ranges, &ranges);
+        check_weights(report, &format!("{}.efficiency", name), &theme.efficiency, &tiers);
+        if theme.adjectives.is_empty() != theme.nouns.is_empty() {
+            report.warning(format!("{}: names need both adjectives and nouns, cards will be named after their effect", name));
+        }
+    }
+}
+
+fn check_names(report: &mut ValidationReport, config: &Config) {
+    let lists = &config.names;
+    for id in lists.effects.keys().filter(|id| config.definition(&Effect::new(id)).is_none()) {
+        report.warning(format!("names.effects.{} is not in effect_catalog and is never used", id));
+    }
+    for id in lists.ranges.keys().filter(|id| !config.range_catalog.iter().any(|definition| definition.matches(id))) {
+        report.warning(format!("names.ranges.{} is not in range_catalog and is never used", id));
+    }
+    for id in lists.rarities.keys().filter(|id| !["Common", "Uncommon", "Rare", "Epic", "Legendary"].iter().any(|rarity| same_id(rarity, id))) {
+        report.warning(format!("names.rarities.{} is not a rarity and is never used", id));
+    }
+    for (name, words) in lists.effects.iter().chain(lists.ranges.iter()).chain(lists.rarities.iter()) {
+        if words.iter().any(|word| word.trim().is_empty()) {
+            report.warning(format!("names: the word list for {} has an empty word", name));
+        }
+    }
+}
+
+fn check_rules_templates(report: &mut ValidationReport, config: &Config) {
+    for (index, template) in config.rules_templates.iter().enumerate() {
+        let name = match template.range_id.as_ref() {
+            Some(range) => format!("rules_templates.{}.{}", template.effect_id, range),
+            None => format!("rules_templates.{}", template.effect_id),
+        };
+        let same_range = |other: &RulesTemplate| match (other.range_id.as_ref(), template.range_id.as_ref()) {
+            (Some(range), Some(other)) => same_id(range, other),
+            (range, other) => range.is_none() && other.is_none(),
+        };
+        if config.rules_templates[..index].iter().any(|other| same_id(&other.effect_id, &template.effect_id) && same_range(other)) {
+            report.warning(format!("{}: more than one template for this effect and range, only the first is used", name));
+        }
+        if config.definition(&Effect::new(&template.effect_id)).is_none() {
+            report.warning(format!("{}: {} is not in effect_catalog and the template is never used", name, template.effect_id));
+        }
+        if template.range_id.as_ref().is_some_and(|range| !config.range_catalog.iter().any(|definition| definition.matches(range))) {
+            report.warning(format!("{}: the range is not in range_catalog and the template is never used", name));
+        }
+        for placeholder in template.text.split('{').skip(1).filter_map(|part| part.split_once('}').map(|(placeholder, _)| placeholder)) {
+            if !PLACEHOLDERS.contains(&placeholder) {
+                report.warning(format!("{}: {{{}}} is not a placeholder and is printed as is (placeholders: {})", name, placeholder, PLACEHOLDERS.join(", ")));
+            }
+        }
+    }
+}
+
+fn check_simulation(report: &mut ValidationReport, rules: &MatchRules) {
+    if rules.party_size == 0 {
+        report.error(String::from("simulation.party_size must be at least 1"));
+    }
+    if rules.room_size > rules.party_size {
+        report.warning(format!("simulation.room_size ({}) is larger than simulation.party_size ({}), AoE will hit the whole party", rules.room_size, rules.party_size));
+    }
+    if rules.member_health <= 0 {
+        report.error(format!("simulation.member_health must be positive (found {})", rules.member_health));
+    }
+    if rules.barnacles_per_turn < 0 || rules.starting_barnacles < 0 {
+        report.error(String::from("simulation barnacle income cannot be negative"));
+    }
+    if rules.max_turns <= 0 {
+        report.error(format!("simulation.max_turns must be positive (found {})", rules.max_turns));
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let ranges = [
+            ("common", &self.rarity_ranges.common),
+            ("uncommon", &self.rarity_ranges.uncommon),
+            ("rare", &self.rarity_ranges.rare),
+            ("epic", &self.rarity_ranges.epic),
+            ("legendary", &self.rarity_ranges.legendary),
+        ];
+        for (name, range) in ranges.iter() {
+            if range.min > range.max {
+                report.error(format!("rarity_ranges.{}: min ({}) is greater than max ({})", name, range.min, range.max));
+            }
+            if range.min < 1 {
+                report.error(format!("rarity_ranges.{}: min must be at least 1 (found {})", name, range.min));
+            }
+        }
+        for pair in ranges.windows(2) {
+            let ((name, range), (next_name, next_range)) = (pair[0], pair[1]);
+            if next_range.max < range.max || next_range.min < range.min {
+                report.warning(format!("rarity_ranges.{} is weaker than rarity_ranges.{}", next_name, name));
+            } else if range.max >= next_range.min {
+                report.warning(format!("rarity_ranges.{} ({}..{}) overlaps rarity_ranges.{} ({}..{})", name, range.min, range.max, next_name, next_range.min, next_range.max));
+            }
+        }
+
+        let priority = &self.power_to_priority;
+        for (name, value) in [("common", priority.common), ("uncommon", priority.uncommon), ("rare", priority.rare), ("epic", priority.epic), ("legendary", priority.legendary)] {
+            if !value.is_finite() || value <= 0.0 {
+                report.error(format!("power_to_priority.{} must be a positive number (found {})", name, value));
+            }
+        }
+
+        check_priority(&mut report, &self.priority);
+        check_effect_catalog(&mut report, self);
+        check_range_catalog(&mut report, self);
+        check_barnacle_pricing(&mut report, self);
+        let conditions = &self.condition_costs;
+        for (name, multiplier) in [("priority_above", conditions.priority_above), ("target_shielded", conditions.target_shielded), ("on_withdraw", conditions.on_withdraw)] {
+            check_multiplier(&mut report, format!("condition_costs.{}", name), multiplier);
+        }
+        if self.extra_effect_surcharge < 0 {
+            report.error(format!("extra_effect_surcharge cannot be negative (found {})", self.extra_effect_surcharge));
+        }
+        check_themes(&mut report, self);
+        check_names(&mut report, self);
+        check_rules_templates(&mut report, self);
+        check_simulation(&mut report, &self.simulation);
+        report
+    }
+}
+
+// Power budgets are rolled, so every card is built with a handful of fixed seeds
+const DECK_CHECK_ROLLS: u64 = 8;
+
+impl DeckInputs {
+    pub fn validate(&self, config: &Config) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for (index, input) in self.inputs.iter().enumerate() {
+            if self.inputs[..index].iter().any(|other| other.name == input.name) {
+                report.warning(format!("{}: more than one card has this name, their card files will overwrite each other", input.name));
+            }
+            if input.effects.is_empty() {
+                report.error(format!("{}: has no effects", input.name));
+                continue;
+            }
+            if let Some(effect) = input.effects.iter().find(|effect| config.aimed_at_own_side(effect, &input.range)) {
+                report.error(format!("{}: {} is aimed at enemies but {} only reaches the caster's side", input.name, effect.id, input.range.id));
+                continue;
+            }
+            let failures: Vec<String> = (0..DECK_CHECK_ROLLS)
+                .filter_map(|seed| input.build(config, &mut StdRng::seed_from_u64(seed)).err())
+                .collect();
+            if let Some(err) = failures.first() {
+                let message = format!("{}: fails to build in {} of {} rolls ({})", input.name, failures.len(), DECK_CHECK_ROLLS, err);
+                if failures.len() as u64 == DECK_CHECK_ROLLS {
+                    report.error(message);
+                } else {
+                    report.warning(message);
+                }
+            }
+            for effect in input.effects.iter() {
+                let mechanic = config.mechanic(effect);
+                if let (Some(Mechanic::Search), Some(rarity)) = (mechanic, &effect.rarity) {
+                    let found = self.inputs.iter().enumerate().any(|(other, card)| other != index && card.rarity == *rarity);
+                    if !found {
+                        report.error(format!("{}: searches for {} cards but the deck has no other {} card", input.name, rarity.label(), rarity.label()));
+                    }
+                }
+                if mechanic == Some(Mechanic::ReturnToHand) {
+                    let only_returns = |card: &CardInput| card.effects.iter().all(|effect| config.mechanic(effect) == Some(Mechanic::ReturnToHand));
+                    if !self.inputs.iter().enumerate().any(|(other, card)| other != index && !only_returns(card)) {
+                        report.warning(format!("{}: returns cards to hand but every other card in the deck only returns cards too", input.name));
+                    }
+                }
+            }
+        }
+        let draws = self.inputs.iter().flat_map(|input| input.effects.iter()).filter(|effect| config.mechanic(effect) == Some(Mechanic::Draw));
+        if draws.count() >= self.inputs.len() {
+            report.warning(format!("every card in the deck draws, the {} card deck will be drawn through in a couple of turns", self.inputs.len()));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        let report = Config::default().validate();
+        assert_eq!(report.errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn config_check_lists_every_problem() {
+        let mut config = Config::default();
+        config.rarity_ranges.rare.min = config.rarity_ranges.rare.max + 1;
+        config.power_to_priority.epic = 0.0;
+        config.effect_catalog[0].barnacle_multiplier = -1.0;
+        config.simulation.max_turns = 0;
+        let report = config.validate();
+        assert_eq!(report.errors.len(), 4, "{:?}", report.errors);
+        assert!(report.errors.iter().any(|error| error.starts_with("rarity_ranges.rare: min")));
+        assert!(report.errors.iter().any(|error| error.starts_with("power_to_priority.epic")));
+        assert!(report.errors.iter().any(|error| error.starts_with("effect_catalog.Damage.barnacle_multiplier")));
+        assert!(report.errors.iter().any(|error| error.starts_with("simulation.max_turns")));
+    }
+
+    #[test]
+    fn deck_check_rejects_cards_without_effects() {
+        let mut deck = DeckInputs::new(DeckType::Starter);
+        deck.inputs[0].effects.clear();
+        let report = deck.validate(&Config::default());
+        assert_eq!(report.errors, vec![format!("{}: has no effects", deck.inputs[0].name)]);
+    }
+
+    #[test]
+    fn deck_check_warns_when_nothing_else_can_be_returned() {
+        let return_to_hand = |card: &mut CardInput| card.effects = vec![Effect { id: String::from("ReturnToHand"), ..card.effects[0].clone() }];
+        let mut deck = DeckInputs::new(DeckType::Starter);
+        deck.inputs.iter_mut().skip(1).for_each(return_to_hand);
+        let warning = |deck: &DeckInputs, index: usize| format!("{}: returns cards to hand but every other card in the deck only returns cards too", deck.inputs[index].name);
+        assert!(!deck.validate(&Config::default()).warnings.contains(&warning(&deck, 1)));
+        return_to_hand(&mut deck.inputs[0]);
+        assert!(deck.validate(&Config::default()).warnings.contains(&warning(&deck, 1)));
+    }
+
+    #[test]
+    fn deck_check_rejects_offensive_effects_on_caster_ranges() {
+        let config = Config::default();
+        let mut deck = DeckInputs::new(DeckType::Starter);
+        deck.inputs[0].range = Range::new("Self");
+        deck.inputs[0].effects = vec![Effect::new("Heal")];
+        assert!(deck.validate(&config).errors.is_empty());
+        deck.inputs[0].effects = vec![Effect::new("Stun")];
+        assert_eq!(deck.validate(&config).errors, vec![format!("{}: Stun is aimed at enemies but Self only reaches the caster's side", deck.inputs[0].name)]);
+    }
+}